@@ -0,0 +1,471 @@
+use crate::state::Board;
+use super::*;
+
+// Fumen's playfield is our 23 rows plus a garbage row underneath that we never fill
+const FIELD_BLOCKS: usize = 240;
+const BOARD_BLOCKS: usize = 230;
+const ENCODE_TABLE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+// Comments are packed four printable ASCII characters at a time, in base 96
+const COMMENT_TABLE_LEN: u32 = 96;
+const MAX_COMMENT_LENGTH: usize = 4095;
+
+type Field = [u8; FIELD_BLOCKS];
+
+fn field_from_board(board: &Board) -> Field {
+    let mut field = [0; FIELD_BLOCKS];
+    for r in 0..23 {
+        for c in 0..10 {
+            field[r * 10 + c] = tile_code(board.tiles[r][c]);
+        }
+    }
+    field
+}
+
+fn board_from_field(field: &Field) -> Board {
+    let mut board = Board::new();
+    for r in 0..23 {
+        for c in 0..10 {
+            board.tiles[r][c] = tile_from_code(field[r * 10 + c]);
+        }
+    }
+    board
+}
+
+fn push_value(data: &mut Vec<u8>, mut value: u32, length: usize) {
+    for _ in 0..length {
+        data.push(ENCODE_TABLE[(value % 64) as usize]);
+        value /= 64;
+    }
+}
+
+pub fn encode(pages: &[FumenPage]) -> String {
+    let mut data: Vec<u8> = Vec::new();
+    let mut prev_field = [0; FIELD_BLOCKS];
+    let mut prev_comment = String::new();
+    // Unchanged fields are written once, followed by how many pages after it repeat it
+    let mut repeat_index: Option<usize> = None;
+
+    for (index, page) in pages.iter().enumerate() {
+        let field = field_from_board(&page.board);
+        if field != prev_field || index == 0 {
+            repeat_index = None;
+        }
+        match repeat_index {
+            Some(i) if data[i] != ENCODE_TABLE[63] => {
+                let count = ENCODE_TABLE.iter().position(|&ch| ch == data[i]).unwrap() as u8;
+                data[i] = ENCODE_TABLE[count as usize + 1];
+            },
+            _ => {
+                // Run-length encode the difference from the previous page's field
+                let diffs: Vec<u32> = (0..FIELD_BLOCKS).map(|i| (field[i] + 8 - prev_field[i]) as u32).collect();
+                let mut start = 0;
+                for i in 1..=FIELD_BLOCKS {
+                    if i == FIELD_BLOCKS || diffs[i] != diffs[start] {
+                        push_value(&mut data, diffs[start] * FIELD_BLOCKS as u32 + (i - start - 1) as u32, 2);
+                        start = i;
+                    }
+                }
+                if field == prev_field {
+                    data.push(ENCODE_TABLE[0]);
+                    repeat_index = Some(data.len() - 1);
+                }
+            },
+        }
+
+        // Then the page's action: the piece, whether it locks, and which flags are set
+        let (piece, rotation, position) = page.operation.and_then(encode_position)
+            .map(|(piece, rotation, position)| (piece_code(piece) as u32, rotation, position))
+            .unwrap_or((0, 0, 0));
+        let has_comment = page.comment != prev_comment;
+        // The outermost flag is "doesn't lock", which is never set since every page we write locks
+        let mut action = has_comment as u32;
+        action = action * 2 + (index == 0) as u32; // Guideline colors, only stored on the first page
+        action *= 2; // Mirror
+        action *= 2; // Rise
+        action = action * FIELD_BLOCKS as u32 + position;
+        action = action * 4 + rotation;
+        action = action * 8 + piece;
+        push_value(&mut data, action, 3);
+
+        if has_comment {
+            let escaped: Vec<u8> = escape(&page.comment).into_bytes().into_iter().take(MAX_COMMENT_LENGTH).collect();
+            push_value(&mut data, escaped.len() as u32, 2);
+            for chunk in escaped.chunks(4) {
+                let value = chunk.iter().rev().fold(0, |value, &ch| value * COMMENT_TABLE_LEN + (ch - b' ') as u32);
+                push_value(&mut data, value, 5);
+            }
+            prev_comment = page.comment.clone();
+        }
+
+        prev_field = field_from_board(&page.next_board());
+    }
+
+    // Fumen breaks the data up with a '?' after the first 42 characters, then every 47
+    let mut fumen = String::from("v115@");
+    for (i, &ch) in data.iter().enumerate() {
+        if i >= 42 && (i - 42).is_multiple_of(47) {
+            fumen.push('?');
+        }
+        fumen.push(ch as char);
+    }
+    fumen
+}
+
+struct Reader {
+    values: Vec<u32>,
+    pos: usize,
+}
+
+impl Reader {
+    fn is_done(&self) -> bool {
+        self.pos >= self.values.len()
+    }
+
+    fn read(&mut self, length: usize) -> Result<u32, &'static str> {
+        if self.pos + length > self.values.len() {
+            return Err("Fumen ends unexpectedly");
+        }
+        let value = self.values[self.pos..self.pos + length].iter().rev().fold(0, |value, &v| value * 64 + v);
+        self.pos += length;
+        Ok(value)
+    }
+}
+
+// Accepts either the bare data ("v115@...") or a full link to any fumen viewer
+pub fn decode(fumen: &str) -> Result<Vec<FumenPage>, &'static str> {
+    let start = fumen.find("115@").ok_or("Only v115 fumens are supported")?;
+    let values = fumen[start + 4..].chars()
+        .filter(|&ch| ch != '?' && !ch.is_whitespace())
+        .take_while(|&ch| ch.is_ascii() && ENCODE_TABLE.contains(&(ch as u8)))
+        .map(|ch| ENCODE_TABLE.iter().position(|&e| e == ch as u8).unwrap() as u32)
+        .collect();
+    let mut reader = Reader { values, pos: 0 };
+    if reader.is_done() {
+        return Err("Fumen has no pages");
+    }
+
+    let mut pages = Vec::new();
+    let mut prev_field: Field = [0; FIELD_BLOCKS];
+    let mut prev_comment = String::new();
+    let mut repeats = 0;
+
+    while !reader.is_done() {
+        let mut field = prev_field;
+        if repeats > 0 {
+            repeats -= 1;
+        } else {
+            let mut i = 0;
+            while i < FIELD_BLOCKS {
+                let value = reader.read(2)?;
+                let diff = value as usize / FIELD_BLOCKS;
+                let count = value as usize % FIELD_BLOCKS + 1;
+                if diff > 16 || i + count > FIELD_BLOCKS {
+                    return Err("Fumen field is malformed");
+                }
+                for cell in field[i..i + count].iter_mut() {
+                    let code = *cell as usize + diff;
+                    if !(8..=16).contains(&code) {
+                        return Err("Fumen field is malformed");
+                    }
+                    *cell = (code - 8) as u8;
+                }
+                // A field identical to the last one also says how many pages after it repeat it
+                if i == 0 && count == FIELD_BLOCKS && diff == 8 {
+                    repeats = reader.read(1)?;
+                }
+                i += count;
+            }
+        }
+
+        let mut action = reader.read(3)?;
+        let piece = piece_from_code((action % 8) as u8);
+        action /= 8;
+        let rotation = rotation_from_code(action % 4);
+        action /= 4;
+        let position = action % FIELD_BLOCKS as u32;
+        action /= FIELD_BLOCKS as u32;
+        let rise = action % 2 == 1;
+        let mirror = (action / 2) % 2 == 1;
+        let has_comment = (action / 8) % 2 == 1;
+        let lock = (action / 16) % 2 == 0;
+
+        let operation = match piece {
+            Some(piece) => Some(decode_position(piece, rotation, position).ok_or("Fumen piece is off the board")?),
+            None => None,
+        };
+
+        if has_comment {
+            let length = reader.read(2)? as usize;
+            let mut escaped = String::new();
+            while escaped.len() < length {
+                let mut value = reader.read(5)?;
+                for _ in 0..4 {
+                    if escaped.len() < length {
+                        escaped.push((b' ' + (value % COMMENT_TABLE_LEN) as u8) as char);
+                    }
+                    value /= COMMENT_TABLE_LEN;
+                }
+            }
+            prev_comment = unescape(&escaped);
+        }
+
+        pages.push(FumenPage {
+            board: board_from_field(&field),
+            operation,
+            comment: prev_comment.clone(),
+        });
+
+        // Work out the field the next page's diff applies to
+        if lock {
+            if let Some(placement) = operation {
+                for cell in placement_cells(placement) {
+                    field[cell] = piece.map(piece_code).unwrap_or(0);
+                }
+            }
+            clear_lines(&mut field);
+            if rise {
+                field.copy_within(10.., 0);
+                field[BOARD_BLOCKS..].fill(0);
+            }
+            if mirror {
+                for row in field[..BOARD_BLOCKS].chunks_mut(10) {
+                    row.reverse();
+                }
+            }
+        }
+        prev_field = field;
+    }
+    Ok(pages)
+}
+
+// Only the board rows clear -- the garbage row is there to be risen into the board
+fn clear_lines(field: &mut Field) {
+    let mut kept: Vec<[u8; 10]> = field[..BOARD_BLOCKS].chunks(10)
+        .filter(|row| row.contains(&0))
+        .map(|row| row.try_into().unwrap())
+        .collect();
+    while kept.len() < 23 {
+        kept.insert(0, [0; 10]);
+    }
+    for (r, row) in kept.iter().enumerate() {
+        field[r * 10..r * 10 + 10].copy_from_slice(row);
+    }
+}
+
+// Comments are stored using JavaScript's escape(), so that they fit in printable ASCII
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for unit in text.encode_utf16() {
+        match char::from_u32(unit as u32) {
+            Some(ch) if ch.is_ascii_alphanumeric() || "@*_+-./".contains(ch) => escaped.push(ch),
+            _ if unit < 256 => escaped.push_str(&format!("%{:02X}", unit)),
+            _ => escaped.push_str(&format!("%u{:04X}", unit)),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut units: Vec<u16> = Vec::new();
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let (unit, length) = if let Some(hex) = rest.strip_prefix("%u").and_then(|r| r.get(..4)) {
+            (u16::from_str_radix(hex, 16).ok(), 6)
+        } else if let Some(hex) = rest.strip_prefix('%').and_then(|r| r.get(..2)) {
+            (u16::from_str_radix(hex, 16).ok(), 3)
+        } else {
+            (None, 1)
+        };
+        match unit {
+            Some(unit) => {
+                units.push(unit);
+                i += length;
+            },
+            None => {
+                // Anything that isn't a valid escape is kept as is
+                let ch = rest.chars().next().unwrap();
+                let mut buf = [0; 2];
+                units.extend_from_slice(ch.encode_utf16(&mut buf));
+                i += ch.len_utf8();
+            },
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::Placement;
+    use crate::state::{Piece, Rotation, Tile};
+
+    fn t_on_floor() -> Placement {
+        Placement::place(Piece::T, 22, 4, Rotation::Normal)
+    }
+
+    fn t_on_floor_board() -> Board {
+        FumenPage::with_operation(Board::new(), t_on_floor(), "").next_board()
+    }
+
+    // A few rows of mixed pieces and garbage, with no full lines
+    fn messy_board() -> Board {
+        let mut board = Board::new();
+        for c in 0..9 {
+            board.tiles[22][c] = Tile::garbage();
+        }
+        for (c, piece) in [Piece::I, Piece::L, Piece::O, Piece::Z, Piece::T, Piece::J, Piece::S].into_iter().enumerate() {
+            board.tiles[21][c + 2] = Tile::from(piece);
+        }
+        board.tiles[3][0] = Tile::from(Piece::Z);
+        board
+    }
+
+    #[test]
+    fn decodes_empty_field() {
+        let pages = decode("v115@vhAAgH").unwrap();
+        assert_eq!(pages, vec![FumenPage::new(Board::new())]);
+        assert_eq!(encode(&pages), "v115@vhAAgH");
+    }
+
+    #[test]
+    fn decodes_links() {
+        let pages = decode("https://fumen.zui.jp/?v115@vhAVQJ").unwrap();
+        assert_eq!(pages, vec![FumenPage::with_operation(Board::new(), t_on_floor(), "")]);
+        assert_eq!(encode(&pages), "v115@vhAVQJ");
+    }
+
+    #[test]
+    fn rejects_bad_fumens() {
+        assert!(decode("v110@vhAAgH").is_err());
+        assert!(decode("v115@").is_err());
+        assert!(decode("v115@vhAA").is_err());
+    }
+
+    #[test]
+    fn repeated_fields() {
+        // The byte after an unchanged field counts the pages after it that repeat it
+        let pages = decode("v115@vhBAgHAAA").unwrap();
+        assert_eq!(pages, vec![FumenPage::new(Board::new()); 2]);
+        assert_eq!(encode(&pages), "v115@vhBAgHAAA");
+        // One count only goes up to 63 pages, then the field is written out again
+        let pages = vec![FumenPage::new(Board::new()); 70];
+        let fumen = encode(&pages);
+        assert!(fumen.starts_with("v115@vh/AgH"));
+        assert_eq!(decode(&fumen).unwrap(), pages);
+    }
+
+    #[test]
+    fn escaped_comments() {
+        let pages = decode("v115@vhAAgWLAQF88AwXv/AWxvAA").unwrap();
+        assert_eq!(pages[0].comment, "PC \u{2665}");
+        assert_eq!(escape("PC \u{2665}"), "PC%20%u2665");
+        assert_eq!(unescape("PC%20%u2665"), "PC \u{2665}");
+        // Pages without a comment of their own keep the one before
+        let pages = decode("v115@vhAAgWLAQF88AwXv/AWxvAAvhAAAA").unwrap();
+        assert_eq!(pages[1].comment, "PC \u{2665}");
+        // Stray percent signs are kept as they are
+        assert_eq!(unescape("100%"), "100%");
+        assert_eq!(unescape("%zz"), "%zz");
+        // Characters outside the BMP go through as surrogate pairs
+        let comment = "2ライン PC: 100% \u{1F600}";
+        assert!(escape(comment).ends_with("%uD83D%uDE00"));
+        let pages = vec![
+            FumenPage::with_operation(Board::new(), t_on_floor(), comment),
+            FumenPage::with_operation(t_on_floor_board(), Placement::place(Piece::T, 20, 4, Rotation::Normal), comment),
+            FumenPage::new(t_on_floor_board()),
+        ];
+        assert_eq!(decode(&encode(&pages)).unwrap(), pages);
+    }
+
+    #[test]
+    fn unlocked_pieces() {
+        // The piece on the first page has the lock flag cleared, so it's gone on the next
+        let pages = decode("v115@vhAVQnvhAAAA").unwrap();
+        assert_eq!(pages[0].operation, Some(t_on_floor()));
+        assert!(pages[1].board.is_empty());
+    }
+
+    #[test]
+    fn rising_garbage() {
+        // The garbage row under the board comes up into it on the next page
+        let pages = decode("v115@mhI8AYJvhAAAA").unwrap();
+        assert!(pages[0].board.is_empty());
+        let mut risen = Board::new();
+        for c in 1..10 {
+            risen.tiles[22][c] = Tile::garbage();
+        }
+        assert_eq!(pages[1].board, risen);
+    }
+
+    #[test]
+    fn mirrored_field() {
+        let pages = decode("v115@bhwhSeAQLvhAAAA").unwrap();
+        assert_eq!(pages[0].board.tiles[22][0], Tile::from(Piece::I));
+        let mut mirrored = Board::new();
+        mirrored.tiles[22][9] = Tile::from(Piece::I);
+        assert_eq!(pages[1].board, mirrored);
+    }
+
+    #[test]
+    fn breaks_long_fumens_up() {
+        let mut pages = Vec::new();
+        let mut board = messy_board();
+        for (i, col) in [1, 4, 7, 1, 4, 7, 1, 4].into_iter().enumerate() {
+            let page = FumenPage::with_operation(board, Placement::place(Piece::T, 20 - i as u8 * 2, col, Rotation::Normal), &format!("page {}", i));
+            board = page.next_board();
+            pages.push(page);
+        }
+        let fumen = encode(&pages);
+        let data = &fumen["v115@".len()..];
+        assert!(data.len() > 42 + 47 * 2);
+        // A '?' after the first 42 characters, then after every 47
+        let marks: Vec<usize> = data.match_indices('?').map(|(i, _)| i).collect();
+        let expected: Vec<usize> = (0..marks.len()).map(|n| 42 + n * 48).collect();
+        assert_eq!(marks, expected);
+        assert_eq!(decode(&fumen).unwrap(), pages);
+    }
+
+    #[test]
+    fn round_trips() {
+        let placements = [
+            Placement::place(Piece::I, 20, 4, Rotation::Normal),
+            Placement::place(Piece::I, 18, 0, Rotation::Cw),
+            Placement::place(Piece::I, 18, 9, Rotation::Ccw),
+            Placement::place(Piece::I, 19, 5, Rotation::Flip),
+            Placement::place(Piece::O, 19, 2, Rotation::Normal),
+            Placement::place(Piece::S, 17, 6, Rotation::Normal),
+            Placement::place(Piece::S, 16, 3, Rotation::Cw),
+            Placement::place(Piece::Z, 16, 7, Rotation::Ccw),
+            Placement::place(Piece::Z, 14, 4, Rotation::Flip),
+            Placement::place(Piece::L, 13, 1, Rotation::Cw),
+            Placement::place(Piece::J, 13, 8, Rotation::Flip),
+            Placement::place(Piece::T, 11, 5, Rotation::Ccw),
+        ];
+        let mut pages = vec![FumenPage::new(messy_board())];
+        let mut board = messy_board();
+        for (i, &placement) in placements.iter().enumerate() {
+            let comment = if i % 3 == 0 { format!("step {}", i) } else { pages.last().unwrap().comment.clone() };
+            let page = FumenPage::with_operation(board, placement, &comment);
+            board = page.next_board();
+            pages.push(page);
+        }
+        // Boards don't have to follow on from the page before
+        pages.push(FumenPage::new(Board::new()));
+        pages.push(FumenPage::new(messy_board()));
+        assert_eq!(decode(&encode(&pages)).unwrap(), pages);
+    }
+
+    #[test]
+    fn round_trips_line_clears() {
+        let mut board = Board::new();
+        for c in 0..9 {
+            board.tiles[22][c] = Tile::garbage();
+            board.tiles[21][c] = Tile::from(Piece::J);
+        }
+        let first = FumenPage::with_operation(board, Placement::place(Piece::I, 20, 9, Rotation::Cw), "");
+        let pages = vec![first.clone(), FumenPage::new(first.next_board())];
+        assert_eq!(pages[1].board.tiles[22][9], Tile::from(Piece::I));
+        assert_eq!(decode(&encode(&pages)).unwrap(), pages);
+    }
+}
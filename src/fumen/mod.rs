@@ -0,0 +1,7 @@
+mod codec;
+mod page;
+mod position;
+
+pub use codec::*;
+pub use page::*;
+use position::*;
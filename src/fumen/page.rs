@@ -0,0 +1,93 @@
+use crate::search::Placement;
+use crate::state::{Board, Piece, Tile};
+
+// Where copied fumens point to, so they can be opened straight from the clipboard
+pub const FUMEN_URL: &str = "https://fumen.zui.jp/?";
+
+// One page of a fumen: the board before the page's piece is placed, the piece itself, and the
+// page's comment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FumenPage {
+    pub board: Board,
+    pub operation: Option<Placement>,
+    pub comment: String,
+}
+
+impl FumenPage {
+    pub fn new(board: Board) -> Self {
+        Self {
+            board,
+            operation: None,
+            comment: String::new(),
+        }
+    }
+
+    pub fn with_operation(board: Board, operation: Placement, comment: &str) -> Self {
+        Self {
+            board,
+            operation: Some(operation),
+            comment: comment.to_string(),
+        }
+    }
+
+    // The board the next page starts from, with the piece locked in and lines cleared
+    pub fn next_board(&self) -> Board {
+        let mut board = self.board;
+        if let Some(Placement::Place { piece, row, col, rotation }) = self.operation {
+            board = board.with_placement(piece, row, col, rotation);
+        }
+        board.clear_lines();
+        board
+    }
+}
+
+// Fumen numbers its blocks as empty, I, L, O, Z, T, J, S, then gray
+pub(super) fn piece_code(piece: Piece) -> u8 {
+    match piece {
+        Piece::I => 1,
+        Piece::L => 2,
+        Piece::O => 3,
+        Piece::Z => 4,
+        Piece::T => 5,
+        Piece::J => 6,
+        Piece::S => 7,
+    }
+}
+
+pub(super) fn piece_from_code(code: u8) -> Option<Piece> {
+    match code {
+        1 => Some(Piece::I),
+        2 => Some(Piece::L),
+        3 => Some(Piece::O),
+        4 => Some(Piece::Z),
+        5 => Some(Piece::T),
+        6 => Some(Piece::J),
+        7 => Some(Piece::S),
+        _ => None,
+    }
+}
+
+pub(super) fn tile_code(tile: Tile) -> u8 {
+    match tile.piece {
+        Some(piece) => piece_code(piece),
+        None if tile.garbage => 8,
+        None => 0,
+    }
+}
+
+pub(super) fn tile_from_code(code: u8) -> Tile {
+    match piece_from_code(code) {
+        Some(piece) => Tile::from(piece),
+        None if code == 8 => Tile::garbage(),
+        None => Tile::new(),
+    }
+}
+
+// Convenience for the common case of sharing a single board
+pub fn board_to_fumen(board: &Board) -> String {
+    super::encode(&[FumenPage::new(*board)])
+}
+
+pub fn fumen_link(fumen: &str) -> String {
+    format!("{}{}", FUMEN_URL, fumen)
+}
@@ -0,0 +1,112 @@
+use crate::search::Placement;
+use crate::state::{Piece, Rotation};
+
+// Fumen stores a piece by its SRS center in (x, y) coordinates, with y counting up from the
+// bottom row of the board. Our rows count down from the top of the 23-row board, so y = 22 - row.
+
+// Fumen's mino offsets around the center for each piece in spawn orientation, (x, y) with y up
+fn spawn_blocks(piece: Piece) -> [(i8, i8); 4] {
+    match piece {
+        Piece::I => [(0, 0), (-1, 0), (1, 0), (2, 0)],
+        Piece::L => [(0, 0), (-1, 0), (1, 0), (1, 1)],
+        Piece::O => [(0, 0), (1, 0), (0, 1), (1, 1)],
+        Piece::Z => [(0, 0), (1, 0), (0, 1), (-1, 1)],
+        Piece::T => [(0, 0), (-1, 0), (1, 0), (0, 1)],
+        Piece::J => [(0, 0), (-1, 0), (1, 0), (-1, 1)],
+        Piece::S => [(0, 0), (-1, 0), (0, 1), (1, 1)],
+    }
+}
+
+fn fumen_blocks(piece: Piece, rotation: Rotation) -> [(i8, i8); 4] {
+    spawn_blocks(piece).map(|(x, y)| match rotation {
+        Rotation::Normal => (x, y),
+        Rotation::Cw => (y, -x),
+        Rotation::Flip => (-x, -y),
+        Rotation::Ccw => (-y, x),
+    })
+}
+
+// Fumen's rotation numbering is reverse, right, spawn, left
+pub(super) fn rotation_code(rotation: Rotation) -> u32 {
+    match rotation {
+        Rotation::Flip => 0,
+        Rotation::Cw => 1,
+        Rotation::Normal => 2,
+        Rotation::Ccw => 3,
+    }
+}
+
+pub(super) fn rotation_from_code(code: u32) -> Rotation {
+    match code {
+        0 => Rotation::Flip,
+        1 => Rotation::Cw,
+        2 => Rotation::Normal,
+        _ => Rotation::Ccw,
+    }
+}
+
+// v115 predates fumen switching to SRS centers, so a few orientations are stored one cell off
+fn legacy_shift(piece: Piece, rotation: Rotation) -> (i8, i8) {
+    match (piece, rotation) {
+        (Piece::O, Rotation::Ccw) => (-1, 1),
+        (Piece::O, Rotation::Flip) => (-1, 0),
+        (Piece::O, Rotation::Normal) => (0, 1),
+        (Piece::I, Rotation::Flip) => (-1, 0),
+        (Piece::I, Rotation::Ccw) => (0, 1),
+        (Piece::S, Rotation::Normal) => (0, 1),
+        (Piece::S, Rotation::Cw) => (-1, 0),
+        (Piece::Z, Rotation::Normal) => (0, 1),
+        (Piece::Z, Rotation::Ccw) => (1, 0),
+        _ => (0, 0),
+    }
+}
+
+// Finds the center such that the offsets around it land exactly on the given cells. Every offset
+// map contains (0, 0), so the center is always one of the cells.
+fn find_center(cells: &[(i8, i8); 4], offsets: &[(i8, i8); 4]) -> Option<(i8, i8)> {
+    cells.iter().copied().find(|&(a, b)| {
+        offsets.iter().all(|&(da, db)| cells.contains(&(a + da, b + db)))
+    })
+}
+
+// Position field of an action: which cell holds the piece's (legacy) center
+pub(super) fn encode_position(placement: Placement) -> Option<(Piece, u32, u32)> {
+    let Placement::Place { piece, row, col, rotation } = placement else {
+        return None;
+    };
+    let cells = piece.offset_map(rotation)
+        .map(|(offset_row, offset_col)| (col as i8 + offset_col, 22 - (row as i8 + offset_row)));
+    let (x, y) = find_center(&cells, &fumen_blocks(piece, rotation))?;
+    let (shift_x, shift_y) = legacy_shift(piece, rotation);
+    let (x, y) = (x + shift_x, y + shift_y);
+    if !(0..10).contains(&x) || !(0..23).contains(&y) {
+        return None;
+    }
+    Some((piece, rotation_code(rotation), (22 - y) as u32 * 10 + x as u32))
+}
+
+pub(super) fn decode_position(piece: Piece, rotation: Rotation, position: u32) -> Option<Placement> {
+    let (shift_x, shift_y) = legacy_shift(piece, rotation);
+    let x = (position % 10) as i8 - shift_x;
+    let y = 22 - (position / 10) as i8 - shift_y;
+    let cells = fumen_blocks(piece, rotation).map(|(dx, dy)| (22 - (y + dy), x + dx));
+    if cells.iter().any(|&(r, c)| !(0..23).contains(&r) || !(0..10).contains(&c)) {
+        return None;
+    }
+    // Our O covers the same cells in every rotation, so keep it in spawn orientation
+    let rotation = if piece == Piece::O { Rotation::Normal } else { rotation };
+    let (row, col) = find_center(&cells, &piece.offset_map(rotation))?;
+    Some(Placement::place(piece, row as u8, col as u8, rotation))
+}
+
+// Board cells covered by a placement, as indices into fumen's field
+pub(super) fn placement_cells(placement: Placement) -> Vec<usize> {
+    match placement {
+        Placement::Place { piece, row, col, rotation } => piece.offset_map(rotation).iter()
+            .map(|&(offset_row, offset_col)| {
+                (row as i8 + offset_row) as usize * 10 + (col as i8 + offset_col) as usize
+            })
+            .collect(),
+        Placement::Hold => Vec::new(),
+    }
+}
@@ -1,10 +1,13 @@
+pub mod fumen;
 pub mod logic;
+pub mod pattern;
+pub mod rng;
 pub mod search;
 pub mod setups;
 pub mod state;
+#[cfg(feature = "ui")]
 pub mod ui;
+#[cfg(feature = "ui")]
 pub mod util;
 
-mod rng;
-
-pub use rng::rng;
+pub use rng::{random_seed, seeded_rng};
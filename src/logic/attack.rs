@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+use super::{ClearType, Spin};
+
+// Bonus lines sent for each combo, starting from the second clear in a row
+const GUIDELINE_COMBO: [u32; 12] = [1, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5, 5];
+
+// Decides how much garbage each clear sends
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+pub enum AttackTable {
+    Guideline, // Tetris 99 / Puyo Puyo Tetris style
+    Tetrio, // Multiplicative combos, as in TETR.IO
+}
+
+impl AttackTable {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AttackTable::Guideline => "Guideline",
+            AttackTable::Tetrio => "TETR.IO",
+        }
+    }
+
+    // `combo` is the number of line clears in a row including this one, and `b2b` is true when this
+    // clear continues a back-to-back chain
+    pub fn attack(&self, clear: ClearType, combo: u32, b2b: bool, perfect_clear: bool) -> u32 {
+        if clear.lines == 0 {
+            return 0;
+        }
+        let base = base_attack(clear) + b2b as u32;
+        let pc_bonus = if perfect_clear { 10 } else { 0 };
+        let ren = combo.saturating_sub(1);
+
+        match self {
+            AttackTable::Guideline => {
+                let combo_bonus = if ren == 0 {
+                    0
+                } else {
+                    GUIDELINE_COMBO[(ren as usize - 1).min(GUIDELINE_COMBO.len() - 1)]
+                };
+                base + combo_bonus + pc_bonus
+            },
+            AttackTable::Tetrio => {
+                let attack = if base > 0 {
+                    base as f32 * (1.0 + 0.25 * ren as f32)
+                } else if ren >= 2 {
+                    // Even clears that send nothing start sending something during long combos
+                    (ren as f32 * 1.25).ln_1p()
+                } else {
+                    0.0
+                };
+                attack.floor() as u32 + pc_bonus
+            },
+        }
+    }
+}
+
+// Lines sent by the clear itself, before any bonuses -- the same for every table
+fn base_attack(clear: ClearType) -> u32 {
+    let lines = clear.lines as u32;
+    match clear.spin {
+        Spin::Full => 2 * lines,
+        Spin::Mini => lines - 1,
+        Spin::None => match lines {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            _ => 4,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::detect_spin;
+    use crate::search::BitBoard;
+    use crate::state::{Piece, Rotation};
+
+    fn clear(spin: Spin, lines: u8) -> ClearType {
+        ClearType::new(if spin == Spin::None { Piece::I } else { Piece::T }, spin, lines)
+    }
+
+    #[test]
+    fn base_attacks() {
+        let table = AttackTable::Guideline;
+        let sent = |spin, lines| table.attack(clear(spin, lines), 1, false, false);
+        assert_eq!([0, 1, 2, 3, 4].map(|lines| sent(Spin::None, lines)), [0, 0, 1, 2, 4]);
+        assert_eq!([0, 1, 2, 3].map(|lines| sent(Spin::Full, lines)), [0, 2, 4, 6]);
+        assert_eq!([0, 1, 2].map(|lines| sent(Spin::Mini, lines)), [0, 0, 1]);
+    }
+
+    #[test]
+    fn guideline_bonuses() {
+        let table = AttackTable::Guideline;
+        // Back-to-back adds one line
+        assert_eq!(table.attack(clear(Spin::None, 4), 1, true, false), 5);
+        assert_eq!(table.attack(clear(Spin::Full, 2), 1, true, false), 5);
+        // Combos add lines from the second clear in a row, topping out at 5
+        let singles: Vec<u32> = (1..=16).map(|combo| table.attack(clear(Spin::None, 1), combo, false, false)).collect();
+        assert_eq!(singles, [0, 1, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5, 5, 5, 5, 5]);
+        // Perfect clears add 10 on top of everything else
+        assert_eq!(table.attack(clear(Spin::None, 2), 1, false, true), 11);
+        assert_eq!(table.attack(clear(Spin::None, 4), 3, true, true), 16);
+        // Placements that don't clear never send anything
+        assert_eq!(table.attack(clear(Spin::Full, 0), 5, true, false), 0);
+    }
+
+    #[test]
+    fn tetrio_bonuses() {
+        let table = AttackTable::Tetrio;
+        assert_eq!(table.attack(clear(Spin::None, 4), 1, false, false), 4);
+        assert_eq!(table.attack(clear(Spin::Full, 2), 1, true, false), 5);
+        // Combos multiply the base attack by a quarter per clear after the first, rounded down
+        assert_eq!(table.attack(clear(Spin::None, 4), 3, false, false), 6);
+        assert_eq!(table.attack(clear(Spin::None, 2), 2, false, false), 1);
+        assert_eq!(table.attack(clear(Spin::Full, 2), 5, true, false), 10);
+        // Clears with no base attack only send during long combos
+        let singles: Vec<u32> = (1..=8).map(|combo| table.attack(clear(Spin::None, 1), combo, false, false)).collect();
+        assert_eq!(singles, [0, 0, 1, 1, 1, 1, 2, 2]);
+        assert_eq!(table.attack(clear(Spin::None, 2), 1, false, true), 11);
+    }
+
+    #[test]
+    fn all_spins_send_like_minis() {
+        // An I tucked under a full row, with nowhere to move, clearing the row it's in
+        let mut board = BitBoard::new();
+        board.rows[21] = 0b11_1111_1111;
+        board.rows[22] = 0b11_1111_0000;
+        let spin = detect_spin(&board, Piece::I, 22, 1, Rotation::Normal, Some((0, 0)), true);
+        let table = AttackTable::Guideline;
+        assert_eq!(table.attack(ClearType::new(Piece::I, spin, 1), 1, false, false), 0);
+        // An S-spin double sends what a plain double does, not what a TSD does
+        assert_eq!(table.attack(ClearType::new(Piece::S, Spin::Mini, 2), 1, false, false), 1);
+        assert_eq!(table.attack(ClearType::new(Piece::S, Spin::Mini, 2), 1, true, false), 2);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::search::{BitBoard, SearchState};
+use crate::state::{Piece, Rotation};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Spin {
+    None,
+    Mini,
+    Full,
+}
+
+// What a single placement did -- which piece it was, whether it spun in, and how many lines cleared
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ClearType {
+    pub piece: Piece,
+    pub spin: Spin,
+    pub lines: u8,
+}
+
+impl ClearType {
+    pub fn new(piece: Piece, spin: Spin, lines: u8) -> Self {
+        Self {
+            piece,
+            spin,
+            lines,
+        }
+    }
+
+    pub fn is_spin(&self) -> bool {
+        self.spin != Spin::None
+    }
+
+    // Short name like "TSD", "TSS mini" or "Quad", or None if the placement did nothing notable
+    pub fn label(&self) -> Option<String> {
+        let mini = if self.spin == Spin::Mini { " mini" } else { "" };
+        if self.is_spin() {
+            Some(match self.lines {
+                0 => format!("{:?}-spin{}", self.piece, mini),
+                1 => format!("{:?}SS{}", self.piece, mini),
+                2 => format!("{:?}SD{}", self.piece, mini),
+                3 => format!("{:?}ST{}", self.piece, mini),
+                _ => format!("{:?}SQ{}", self.piece, mini),
+            })
+        } else {
+            match self.lines {
+                0 => None,
+                1 => Some(String::from("Single")),
+                2 => Some(String::from("Double")),
+                3 => Some(String::from("Triple")),
+                _ => Some(String::from("Quad")),
+            }
+        }
+    }
+}
+
+// Works out whether a piece locking at this position counts as a spin. `kick` is the kick offset
+// used by the piece's last move if that move was a rotation, and None otherwise.
+pub fn detect_spin(
+    board: &BitBoard,
+    piece: Piece,
+    row: i8,
+    col: i8,
+    rotation: Rotation,
+    kick: Option<(i8, i8)>,
+    all_spins: bool,
+) -> Spin {
+    let (kick_row, kick_col) = match kick {
+        Some(kick) => kick,
+        None => return Spin::None,
+    };
+
+    if piece == Piece::T {
+        // 3-corner rule -- at least 3 of the corners around the center have to be filled, and it's
+        // only a full T-spin if both corners on the pointy side are filled
+        let filled = |&(offset_row, offset_col): &(i8, i8)| board.is_filled(row + offset_row, col + offset_col);
+        let num_corners = [(-1, -1), (-1, 1), (1, -1), (1, 1)].iter().filter(|corner| filled(corner)).count();
+        if num_corners < 3 {
+            return Spin::None;
+        }
+        let front_corners = match rotation {
+            Rotation::Normal => [(-1, -1), (-1, 1)],
+            Rotation::Cw => [(-1, 1), (1, 1)],
+            Rotation::Flip => [(1, -1), (1, 1)],
+            Rotation::Ccw => [(-1, -1), (1, -1)],
+        };
+        // The TST and fin kicks always count as full T-spins
+        if front_corners.iter().all(filled) || (kick_row.abs() == 2 && kick_col.abs() == 1) {
+            Spin::Full
+        } else {
+            Spin::Mini
+        }
+    } else if all_spins && piece != Piece::O {
+        // Other pieces count as spinning in if they can't move in any direction. They only ever
+        // get a mini, so they don't score or send like a T-spin.
+        let immobile = [(-1, 0), (1, 0), (0, -1), (0, 1)].iter().all(|&(shift_row, shift_col)| {
+            SearchState::new(row + shift_row, col + shift_col, rotation, piece).intersects(board)
+        });
+        if immobile {
+            Spin::Mini
+        } else {
+            Spin::None
+        }
+    } else {
+        Spin::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fills the bottom rows of the board, one string per row with 'X' for filled cells
+    fn board(rows: &[&str]) -> BitBoard {
+        let mut board = BitBoard::new();
+        for (i, row) in rows.iter().enumerate() {
+            let r = 23 - rows.len() + i;
+            for (c, ch) in row.chars().enumerate() {
+                if ch == 'X' {
+                    board.rows[r] |= 1 << c;
+                }
+            }
+        }
+        board
+    }
+
+    // T slot on the left with its overhang, for a T pointing down into it
+    fn tsd_slot() -> BitBoard {
+        board(&[
+            "X.X.......",
+            "...XXXXXXX",
+            "X.XXXXXXXX",
+        ])
+    }
+
+    // T slot against the left wall, for a T pointing right into it
+    fn mini_slot() -> BitBoard {
+        board(&[
+            "..........",
+            "..XXXXXXXX",
+            ".XXXXXXXXX",
+        ])
+    }
+
+    #[test]
+    fn full_tspin_needs_both_front_corners() {
+        let spin = detect_spin(&tsd_slot(), Piece::T, 21, 1, Rotation::Flip, Some((0, 0)), false);
+        assert_eq!(spin, Spin::Full);
+    }
+
+    #[test]
+    fn mini_tspin_with_one_front_corner() {
+        // Both back corners are the wall, and only the lower front corner is filled
+        let spin = detect_spin(&mini_slot(), Piece::T, 21, 0, Rotation::Cw, Some((0, 1)), false);
+        assert_eq!(spin, Spin::Mini);
+    }
+
+    #[test]
+    fn tst_kick_upgrades_a_mini() {
+        for kick in [(2, 1), (2, -1), (-2, 1), (-2, -1)] {
+            let spin = detect_spin(&mini_slot(), Piece::T, 21, 0, Rotation::Cw, Some(kick), false);
+            assert_eq!(spin, Spin::Full);
+        }
+        // Other long kicks don't
+        let spin = detect_spin(&mini_slot(), Piece::T, 21, 0, Rotation::Cw, Some((1, 2)), false);
+        assert_eq!(spin, Spin::Mini);
+    }
+
+    #[test]
+    fn tspin_needs_three_corners() {
+        // Only the floor under the T
+        let spin = detect_spin(&BitBoard::new(), Piece::T, 22, 4, Rotation::Normal, Some((0, 0)), false);
+        assert_eq!(spin, Spin::None);
+        let two_corners = board(&[
+            "..........",
+            "..........",
+            "X.XXXXXXXX",
+        ]);
+        let spin = detect_spin(&two_corners, Piece::T, 21, 1, Rotation::Flip, Some((0, 0)), false);
+        assert_eq!(spin, Spin::None);
+    }
+
+    #[test]
+    fn tspin_needs_a_rotation_last() {
+        let spin = detect_spin(&tsd_slot(), Piece::T, 21, 1, Rotation::Flip, None, true);
+        assert_eq!(spin, Spin::None);
+    }
+
+    #[test]
+    fn all_spins_for_stuck_pieces() {
+        // An I tucked under a full row, with nowhere to move
+        let stuck = board(&[
+            "XXXXXXXXXX",
+            "....XXXXXX",
+        ]);
+        assert_eq!(detect_spin(&stuck, Piece::I, 22, 1, Rotation::Normal, Some((0, 0)), true), Spin::Mini);
+        assert_eq!(detect_spin(&stuck, Piece::I, 22, 1, Rotation::Normal, Some((0, 0)), false), Spin::None);
+        // Without the row above, it could still move up
+        let open = board(&[
+            "..........",
+            "....XXXXXX",
+        ]);
+        assert_eq!(detect_spin(&open, Piece::I, 22, 1, Rotation::Normal, Some((0, 0)), true), Spin::None);
+        // O pieces never spin
+        let o_slot = board(&[
+            "XXXXXXXXXX",
+            "..XXXXXXXX",
+            "..XXXXXXXX",
+        ]);
+        assert_eq!(detect_spin(&o_slot, Piece::O, 21, 0, Rotation::Normal, Some((0, 0)), true), Spin::None);
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(ClearType::new(Piece::T, Spin::Full, 2).label().as_deref(), Some("TSD"));
+        assert_eq!(ClearType::new(Piece::T, Spin::Mini, 1).label().as_deref(), Some("TSS mini"));
+        assert_eq!(ClearType::new(Piece::S, Spin::Mini, 0).label().as_deref(), Some("S-spin mini"));
+        assert_eq!(ClearType::new(Piece::I, Spin::None, 4).label().as_deref(), Some("Quad"));
+        assert_eq!(ClearType::new(Piece::L, Spin::None, 0).label(), None);
+    }
+}
@@ -1,41 +1,137 @@
-use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::rng::{random_seed, QueueRandomizer, Randomizer, RandomizerKind};
+use crate::search::PcObjective;
+use crate::state::RotationSystem;
+use super::{key_codes, AttackTable, Key, KeyAction, LockDelayMode, PadButton};
 
+// Fields missing from a saved config (e.g. ones added since it was saved) get their defaults
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
-    pub left: KeyCode,
-    pub right: KeyCode,
-    pub soft_drop: KeyCode,
-    pub hard_drop: KeyCode,
-    pub rotate_cw: KeyCode,
-    pub rotate_ccw: KeyCode,
-    pub rotate_180: KeyCode,
-    pub hold: KeyCode,
-    pub reset: KeyCode,
-    pub undo: KeyCode,
+    #[serde(with = "key_codes")]
+    pub left: Vec<Key>,
+    #[serde(with = "key_codes")]
+    pub right: Vec<Key>,
+    #[serde(with = "key_codes")]
+    pub soft_drop: Vec<Key>,
+    #[serde(with = "key_codes")]
+    pub hard_drop: Vec<Key>,
+    #[serde(with = "key_codes")]
+    pub rotate_cw: Vec<Key>,
+    #[serde(with = "key_codes")]
+    pub rotate_ccw: Vec<Key>,
+    #[serde(with = "key_codes")]
+    pub rotate_180: Vec<Key>,
+    #[serde(with = "key_codes")]
+    pub hold: Vec<Key>,
+    #[serde(with = "key_codes")]
+    pub reset: Vec<Key>,
+    #[serde(with = "key_codes")]
+    pub undo: Vec<Key>,
+    pub pad_bindings: BTreeMap<PadButton, KeyAction>, // What each gamepad button does, if anything
     pub gravity: f32, // Measured in blocks per second
-    pub grace_period: u32, // Milliseconds before gravity places piece that is touching floor
+    pub grace_period: u32, // Lock delay -- milliseconds before a piece that is touching floor is placed
+    pub lock_delay_mode: LockDelayMode, // What restarts the lock delay
     pub das: u32, // Milliseconds before delayed auto-shift activates
     pub arr: u32, // Milliseconds between each movement repetition during DAS
-    pub sdr: u32, // Milliseconds to fall one unit when soft dropping
+    pub dcd: u32, // Milliseconds that auto-shift pauses for after a rotation or a new piece
+    pub das_carry: bool, // Keep a charged DAS through piece spawns instead of charging it again
+    pub sdf: f32, // How many times faster than gravity soft drop falls
+    pub infinite_sdf: bool, // Soft drop straight to the floor
+    pub randomizer: RandomizerKind, // How the queue gets generated
+    pub pin_seed: bool, // Reset replays the same seed instead of rolling a new one
+    pub custom_queue: String, // Typed queue to drill, e.g. "TIJLOSZ" or "*p7"
+    pub loop_custom_queue: bool, // Start the custom queue over when it runs out, instead of going on with 7-bags
+    pub use_custom_queue: bool, // Deal the custom queue instead of using the randomizer
+    pub rotation_system: RotationSystem, // Kicks used by both gameplay and the solvers
+    pub all_spins: bool, // Whether non-T pieces that end up stuck after rotating count as spins
+    pub attack_table: AttackTable, // How much garbage each clear counts as sending
+    pub pc_objective: PcObjective, // Which PC solutions get shown first
+    pub pc_chance_height: u8, // Lines in the PC that the solve chance calculator looks for
+    pub pc_chance_pattern: String, // Queues for the solve chance calculator to try, blank for 7-bag continuations
+    pub coverage_pattern: String, // Queues to check the setups of the current PC against
 }
 
-impl Config {
-    pub fn default() -> Self {
+impl Default for Config {
+    fn default() -> Self {
         Self {
-            left: KeyCode::J,
-            right: KeyCode::L,
-            soft_drop: KeyCode::K,
-            hard_drop: KeyCode::Space,
-            rotate_cw: KeyCode::D,
-            rotate_ccw: KeyCode::A,
-            rotate_180: KeyCode::S,
-            hold: KeyCode::W,
-            reset: KeyCode::R,
-            undo: KeyCode::Z,
+            left: vec![Key::J],
+            right: vec![Key::L],
+            soft_drop: vec![Key::K],
+            hard_drop: vec![Key::Space],
+            rotate_cw: vec![Key::D],
+            rotate_ccw: vec![Key::A],
+            rotate_180: vec![Key::S],
+            hold: vec![Key::W],
+            reset: vec![Key::R],
+            undo: vec![Key::Z],
+            pad_bindings: BTreeMap::from([
+                (PadButton::DPadLeft, KeyAction::Left),
+                (PadButton::DPadRight, KeyAction::Right),
+                (PadButton::DPadDown, KeyAction::SoftDrop),
+                (PadButton::DPadUp, KeyAction::HardDrop),
+                (PadButton::East, KeyAction::RotateCw),
+                (PadButton::South, KeyAction::RotateCcw),
+                (PadButton::North, KeyAction::Rotate180),
+                (PadButton::LeftBumper, KeyAction::Hold),
+                (PadButton::RightBumper, KeyAction::Hold),
+                (PadButton::Start, KeyAction::Reset),
+                (PadButton::Select, KeyAction::Undo),
+            ]),
             gravity: 0.0,
             grace_period: 750,
+            lock_delay_mode: LockDelayMode::MoveReset,
             das: 100,
             arr: 0,
-            sdr: 0,
+            dcd: 0,
+            das_carry: true,
+            sdf: 20.0,
+            infinite_sdf: true,
+            randomizer: RandomizerKind::SevenBag,
+            pin_seed: false,
+            custom_queue: String::new(),
+            loop_custom_queue: true,
+            use_custom_queue: false,
+            rotation_system: RotationSystem::SrsPlus,
+            all_spins: false,
+            attack_table: AttackTable::Guideline,
+            pc_objective: PcObjective::LowestHeight,
+            pc_chance_height: 4,
+            pc_chance_pattern: String::new(),
+            coverage_pattern: String::from("*p7"),
+        }
+    }
+}
+
+impl Config {
+    // Milliseconds to fall one unit when soft dropping, or None if soft drop is instant. With
+    // gravity off, soft drop is a multiple of one block per second instead.
+    pub fn soft_drop_interval(&self) -> Option<u64> {
+        if self.infinite_sdf {
+            return None;
+        }
+        let base = if self.gravity > 0.0 { self.gravity } else { 1.0 };
+        Some((1000.0 / (base * self.sdf.max(1.0))) as u64)
+    }
+
+    // The seed a new game starts from, given the one the current game was dealt from
+    pub fn next_seed(&self, seed: u64) -> u64 {
+        if self.pin_seed {
+            seed
+        } else {
+            random_seed()
+        }
+    }
+
+    // What a new game should deal pieces with -- the randomizer is used if the custom queue is
+    // off or can't be read
+    pub fn create_randomizer(&self) -> Box<dyn Randomizer> {
+        if self.use_custom_queue {
+            if let Ok(randomizer) = QueueRandomizer::new(&self.custom_queue, self.loop_custom_queue) {
+                return Box::new(randomizer);
+            }
         }
+        self.randomizer.create()
     }
 }
@@ -0,0 +1,40 @@
+use crate::state::Piece;
+use super::{ClearType, KeyAction};
+
+// A single press or release fed into the game, timestamped in milliseconds on the game clock
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputEvent {
+    pub time: u64,
+    pub action: KeyAction,
+    pub pressed: bool, // True when the key went down, false when it was released
+}
+
+impl InputEvent {
+    pub fn press(time: u64, action: KeyAction) -> Self {
+        Self {
+            time,
+            action,
+            pressed: true,
+        }
+    }
+
+    pub fn release(time: u64, action: KeyAction) -> Self {
+        Self {
+            time,
+            action,
+            pressed: false,
+        }
+    }
+}
+
+// Things that happened during a game step that a frontend might want to react to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    PieceSpawned(Piece),
+    PiecePlaced(ClearType),
+    Held,
+    Undone,
+    Reset,
+    BoardLoaded,
+    ToppedOut,
+}
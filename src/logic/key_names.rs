@@ -0,0 +1,39 @@
+use strum::IntoEnumIterator;
+use super::Key;
+
+// Keys are saved by name, as they're printed with Debug
+pub fn key_name(key: Key) -> String {
+    format!("{:?}", key)
+}
+
+pub fn key_from_name(name: &str) -> Option<Key> {
+    Key::iter().find(|&key| key_name(key) == name)
+}
+
+// For `#[serde(with = "key_codes")]` on key binding fields
+pub mod key_codes {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use super::{key_from_name, key_name, Key};
+
+    // Configs from before an action could have several keys saved just the one
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Names {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    pub fn serialize<S: Serializer>(keys: &[Key], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(keys.iter().map(|&key| key_name(key)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Key>, D::Error> {
+        let names = match Names::deserialize(deserializer)? {
+            Names::One(name) => vec![name],
+            Names::Many(names) => names,
+        };
+        names.iter()
+            .map(|name| key_from_name(name).ok_or_else(|| D::Error::custom(format!("unknown key `{}`", name))))
+            .collect()
+    }
+}
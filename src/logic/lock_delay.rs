@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+// Moves and rotations on the ground that restart the lock delay under move reset, per piece
+pub const MOVE_RESET_LIMIT: u32 = 15;
+
+// What restarts the lock delay once a piece is on the ground
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+pub enum LockDelayMode {
+    StepReset, // Only falling to a new lowest row
+    MoveReset, // Also moving or rotating, up to 15 times until the piece falls lower
+    Infinity, // Also moving or rotating, as often as you like
+}
+
+impl LockDelayMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LockDelayMode::StepReset => "Step reset",
+            LockDelayMode::MoveReset => "Move reset",
+            LockDelayMode::Infinity => "Infinity",
+        }
+    }
+}
@@ -1,11 +1,27 @@
+mod attack;
+mod clear_type;
 mod config;
+mod event;
 mod helpers;
 mod input;
+mod key;
 mod key_action;
+mod key_names;
+mod lock_delay;
+mod pad_button;
+mod settings;
 mod stats;
 
+pub use attack::*;
+pub use clear_type::*;
 pub use config::*;
+pub use event::*;
 pub use helpers::*;
 pub use input::*;
+pub use key::*;
 pub use key_action::*;
+pub use key_names::*;
+pub use lock_delay::*;
+pub use pad_button::*;
+pub use settings::*;
 pub use stats::*;
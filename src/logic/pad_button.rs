@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+// Gamepad buttons that can be bound, named by their position like in SDL's controller layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter, Serialize, Deserialize)]
+pub enum PadButton {
+    DPadLeft,
+    DPadRight,
+    DPadUp,
+    DPadDown,
+    South,
+    East,
+    West,
+    North,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Guide,
+    LeftStick,
+    RightStick,
+}
+
+impl PadButton {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PadButton::DPadLeft => "D-pad Left",
+            PadButton::DPadRight => "D-pad Right",
+            PadButton::DPadUp => "D-pad Up",
+            PadButton::DPadDown => "D-pad Down",
+            PadButton::South => "A / Cross",
+            PadButton::East => "B / Circle",
+            PadButton::West => "X / Square",
+            PadButton::North => "Y / Triangle",
+            PadButton::LeftBumper => "LB / L1",
+            PadButton::RightBumper => "RB / R1",
+            PadButton::LeftTrigger => "LT / L2",
+            PadButton::RightTrigger => "RT / R2",
+            PadButton::Select => "Select / Back",
+            PadButton::Start => "Start",
+            PadButton::Guide => "Guide / Home",
+            PadButton::LeftStick => "Left Stick Press",
+            PadButton::RightStick => "Right Stick Press",
+        }
+    }
+}
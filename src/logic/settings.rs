@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use super::Config;
+
+// Bumped whenever the config file changes in a way that filling in defaults can't handle, so that
+// older files can be converted when they're loaded
+const CONFIG_VERSION: u32 = 3;
+const DEFAULT_PROFILE: &str = "Default";
+
+#[derive(Serialize, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    version: u32,
+    active: String, // Profile that was in use last
+    #[serde(default)]
+    last_seed: Option<u64>, // Seed of the last game played, so a pinned seed carries over to the next session
+    profiles: BTreeMap<String, Config>,
+}
+
+// Version 2 soft dropped at a fixed SDR in milliseconds per row, with 0 meaning instant, which
+// becomes the SDF that falls about as fast at the profile's gravity
+fn convert_sdr(profile: &mut toml::Table) {
+    let Some(sdr) = profile.remove("sdr").and_then(|sdr| sdr.as_integer()) else {
+        return;
+    };
+    profile.insert(String::from("infinite_sdf"), toml::Value::Boolean(sdr == 0));
+    if sdr > 0 {
+        let gravity = profile.get("gravity").and_then(|gravity| gravity.as_float()).filter(|&gravity| gravity > 0.0);
+        let sdf = 1000.0 / sdr as f64 / gravity.unwrap_or(1.0);
+        profile.insert(String::from("sdf"), toml::Value::Float(sdf.clamp(1.0, 40.0)));
+    }
+}
+
+impl ConfigFile {
+    fn new() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            active: String::from(DEFAULT_PROFILE),
+            last_seed: None,
+            profiles: BTreeMap::from([(String::from(DEFAULT_PROFILE), Config::default())]),
+        }
+    }
+}
+
+// Named configs (handling presets and keybinds), saved in the user's config directory whenever
+// one changes
+pub struct Settings {
+    file: ConfigFile,
+    pub error: Option<String>, // Why the config file couldn't be read or written
+}
+
+impl Settings {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("tetris-tool").join("config.toml"))
+    }
+
+    // Reads the config file, starting over with the defaults if there isn't one. A file that can't
+    // be read is kept next to the new one as config.toml.bak.
+    pub fn load() -> Self {
+        let mut settings = Self {
+            file: ConfigFile::new(),
+            error: None,
+        };
+        let Some(path) = Self::path() else {
+            return settings;
+        };
+        let Ok(text) = fs::read_to_string(&path) else {
+            return settings;
+        };
+        match toml::from_str::<toml::Table>(&text).and_then(Self::convert) {
+            Ok(mut file) => {
+                file.version = CONFIG_VERSION;
+                if file.profiles.is_empty() {
+                    file.profiles.insert(String::from(DEFAULT_PROFILE), Config::default());
+                }
+                if !file.profiles.contains_key(&file.active) {
+                    file.active = file.profiles.keys().next().cloned().unwrap_or_default();
+                }
+                settings.file = file;
+            },
+            Err(err) => {
+                let backup = path.with_extension("toml.bak");
+                let _ = fs::rename(&path, &backup);
+                settings.error = Some(format!("{}: {} (moved to {})", path.display(), err.message(), backup.display()));
+            },
+        }
+        settings
+    }
+
+    // Brings a config file from an older version up to date. Version 1 bound a single key to each
+    // action, which is read as a list of one key without needing any changes.
+    fn convert(mut table: toml::Table) -> Result<ConfigFile, toml::de::Error> {
+        let version = table.get("version").and_then(|version| version.as_integer()).unwrap_or(0);
+        if version < 3 {
+            if let Some(toml::Value::Table(profiles)) = table.get_mut("profiles") {
+                for (_, profile) in profiles.iter_mut() {
+                    if let toml::Value::Table(profile) = profile {
+                        convert_sdr(profile);
+                    }
+                }
+            }
+        }
+        toml::Value::Table(table).try_into()
+    }
+
+    fn save(&mut self) {
+        let Some(path) = Self::path() else {
+            return;
+        };
+        let result = toml::to_string(&self.file).map_err(|err| err.to_string()).and_then(|text| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+            }
+            fs::write(&path, text).map_err(|err| err.to_string())
+        });
+        self.error = result.err().map(|err| format!("{}: {}", path.display(), err));
+    }
+
+    pub fn active(&self) -> &str {
+        &self.file.active
+    }
+
+    pub fn profile_names(&self) -> Vec<String> {
+        self.file.profiles.keys().cloned().collect()
+    }
+
+    // The config of the profile in use
+    pub fn config(&self) -> Config {
+        self.file.profiles.get(&self.file.active).cloned().unwrap_or_default()
+    }
+
+    // Keeps the profile in use up to date with the config, saving if anything changed
+    pub fn update(&mut self, config: &Config) {
+        if self.file.profiles.get(&self.file.active) == Some(config) {
+            return;
+        }
+        self.file.profiles.insert(self.file.active.clone(), config.clone());
+        self.save();
+    }
+
+    pub fn last_seed(&self) -> Option<u64> {
+        self.file.last_seed
+    }
+
+    // Remembers the seed of the game being played, saving if it's a new one
+    pub fn update_seed(&mut self, seed: u64) {
+        if self.file.last_seed == Some(seed) {
+            return;
+        }
+        self.file.last_seed = Some(seed);
+        self.save();
+    }
+
+    // Returns the config of the profile switched to
+    pub fn switch_profile(&mut self, name: &str) -> Option<Config> {
+        let config = self.file.profiles.get(name).cloned()?;
+        self.file.active = name.to_string();
+        self.save();
+        Some(config)
+    }
+
+    // Starts a new profile from a copy of the given config, and switches to it
+    pub fn new_profile(&mut self, name: &str, config: &Config) -> Result<(), &'static str> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Profile name can't be empty");
+        }
+        if self.file.profiles.contains_key(name) {
+            return Err("There is already a profile with that name");
+        }
+        self.file.profiles.insert(name.to_string(), config.clone());
+        self.file.active = name.to_string();
+        self.save();
+        Ok(())
+    }
+
+    // Deletes the profile in use and returns the config of the one switched to instead, unless it
+    // is the only profile
+    pub fn delete_profile(&mut self) -> Option<Config> {
+        if self.file.profiles.len() <= 1 {
+            return None;
+        }
+        self.file.profiles.remove(&self.file.active);
+        let name = self.file.profiles.keys().next().cloned()?;
+        self.switch_profile(&name)
+    }
+}
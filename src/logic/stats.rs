@@ -1,9 +1,37 @@
-#[derive(Clone, Copy)]
+use crate::state::Piece;
+use super::{AttackTable, ClearType, Spin};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClearCounts {
+    pub singles: u32,
+    pub doubles: u32,
+    pub triples: u32,
+    pub quads: u32,
+    pub tspin_minis: u32, // T-spin minis that don't clear anything
+    pub tspins: u32, // T-spins that don't clear anything
+    pub tss_minis: u32,
+    pub tsd_minis: u32,
+    pub tss: u32,
+    pub tsd: u32,
+    pub tst: u32,
+    pub other_spins: u32, // Line clears from non-T spins
+    pub perfect_clears: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
 pub struct Stats {
     pub inputs: u32,
     pub pieces: u32,
     pub lines: u32,
     pub faults: u32,
+    pub last_clear: Option<ClearType>, // Most recent line clear or spin
+    pub score: u32,
+    pub attack: u32, // Total lines of garbage sent
+    pub combo: u32, // Line clears in a row -- so 2 of these is a 1 combo
+    pub max_combo: u32,
+    pub b2b: u32, // Difficult clears in a row -- so 2 of these is a 1 B2B
+    pub max_b2b: u32,
+    pub clear_counts: ClearCounts,
 }
 
 impl Stats {
@@ -13,6 +41,164 @@ impl Stats {
             pieces: 0,
             lines: 0,
             faults: 0,
+            last_clear: None,
+            score: 0,
+            attack: 0,
+            combo: 0,
+            max_combo: 0,
+            b2b: 0,
+            max_b2b: 0,
+            clear_counts: ClearCounts::default(),
+        }
+    }
+
+    // Updates score, combo, back-to-back and attack after a piece locks
+    pub fn record_clear(&mut self, clear: ClearType, perfect_clear: bool, attack_table: AttackTable) {
+        self.lines += clear.lines as u32;
+        if clear.label().is_some() {
+            self.last_clear = Some(clear);
+        }
+        self.count_clear(clear, perfect_clear);
+
+        // Placements that don't clear lines drop the combo but leave back-to-back alone
+        if clear.lines == 0 {
+            self.combo = 0;
+            self.score += clear_score(clear);
+            return;
+        }
+        self.combo += 1;
+        self.max_combo = self.max_combo.max(self.combo);
+        let difficult = clear.lines >= 4 || clear.is_spin();
+        let b2b = difficult && self.b2b > 0;
+        if difficult {
+            self.b2b += 1;
+            self.max_b2b = self.max_b2b.max(self.b2b);
+        } else {
+            self.b2b = 0;
+        }
+
+        let mut score = clear_score(clear);
+        if b2b {
+            score = score * 3 / 2;
+        }
+        score += 50 * (self.combo - 1);
+        if perfect_clear {
+            score += match clear.lines {
+                1 => 800,
+                2 => 1200,
+                3 => 1800,
+                _ if b2b => 3200,
+                _ => 2000,
+            };
+        }
+        self.score += score;
+        self.attack += attack_table.attack(clear, self.combo, b2b, perfect_clear);
+    }
+
+    fn count_clear(&mut self, clear: ClearType, perfect_clear: bool) {
+        let counts = &mut self.clear_counts;
+        if perfect_clear {
+            counts.perfect_clears += 1;
         }
+        let count = match (clear.piece, clear.spin, clear.lines) {
+            (_, Spin::None, 0) => return,
+            (_, Spin::None, 1) => &mut counts.singles,
+            (_, Spin::None, 2) => &mut counts.doubles,
+            (_, Spin::None, 3) => &mut counts.triples,
+            (_, Spin::None, _) => &mut counts.quads,
+            (Piece::T, Spin::Mini, 0) => &mut counts.tspin_minis,
+            (Piece::T, Spin::Mini, 1) => &mut counts.tss_minis,
+            (Piece::T, Spin::Mini, _) => &mut counts.tsd_minis,
+            (Piece::T, Spin::Full, 0) => &mut counts.tspins,
+            (Piece::T, Spin::Full, 1) => &mut counts.tss,
+            (Piece::T, Spin::Full, 2) => &mut counts.tsd,
+            (Piece::T, Spin::Full, _) => &mut counts.tst,
+            (_, _, 0) => return,
+            _ => &mut counts.other_spins,
+        };
+        *count += 1;
+    }
+}
+
+// Guideline points for a clear at level 1, before back-to-back, combo and perfect clear bonuses
+fn clear_score(clear: ClearType) -> u32 {
+    match (clear.spin, clear.lines) {
+        (Spin::None, 0) => 0,
+        (Spin::None, 1) => 100,
+        (Spin::None, 2) => 300,
+        (Spin::None, 3) => 500,
+        (Spin::None, _) => 800,
+        (Spin::Mini, 0) => 100,
+        (Spin::Mini, 1) => 200,
+        (Spin::Mini, _) => 400,
+        (Spin::Full, 0) => 400,
+        (Spin::Full, 1) => 800,
+        (Spin::Full, 2) => 1200,
+        (Spin::Full, _) => 1600,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combo_and_back_to_back() {
+        let mut stats = Stats::new();
+        let table = AttackTable::Guideline;
+        stats.record_clear(ClearType::new(Piece::I, Spin::None, 4), false, table);
+        assert_eq!((stats.combo, stats.b2b, stats.score, stats.attack), (1, 1, 800, 4));
+        // Back-to-back TSD in a 1 combo: 1.5x points plus 50, and 4 + 1 + 1 lines
+        stats.record_clear(ClearType::new(Piece::T, Spin::Full, 2), false, table);
+        assert_eq!((stats.combo, stats.b2b, stats.score, stats.attack), (2, 2, 2650, 10));
+        // Placing without clearing drops the combo but keeps back-to-back
+        stats.record_clear(ClearType::new(Piece::L, Spin::None, 0), false, table);
+        assert_eq!((stats.combo, stats.b2b, stats.score), (0, 2, 2650));
+        stats.record_clear(ClearType::new(Piece::T, Spin::Full, 0), false, table);
+        assert_eq!((stats.combo, stats.b2b, stats.score), (0, 2, 3050));
+        assert_eq!(stats.last_clear, Some(ClearType::new(Piece::T, Spin::Full, 0)));
+        // A plain single breaks back-to-back
+        stats.record_clear(ClearType::new(Piece::J, Spin::None, 1), false, table);
+        assert_eq!((stats.combo, stats.b2b, stats.score, stats.attack), (1, 0, 3150, 10));
+        assert_eq!((stats.max_combo, stats.max_b2b, stats.lines), (2, 2, 7));
+        assert_eq!(stats.last_clear, Some(ClearType::new(Piece::J, Spin::None, 1)));
+        let counts = stats.clear_counts;
+        assert_eq!((counts.quads, counts.tsd, counts.tspins, counts.singles), (1, 1, 1, 1));
+    }
+
+    #[test]
+    fn mini_spins_keep_back_to_back() {
+        let mut stats = Stats::new();
+        let table = AttackTable::Guideline;
+        stats.record_clear(ClearType::new(Piece::T, Spin::Mini, 1), false, table);
+        stats.record_clear(ClearType::new(Piece::S, Spin::Mini, 2), false, table);
+        assert_eq!(stats.b2b, 2);
+        let counts = stats.clear_counts;
+        assert_eq!((counts.tss_minis, counts.other_spins), (1, 1));
+    }
+
+    #[test]
+    fn all_spins_score_like_minis() {
+        let table = AttackTable::Guideline;
+        let mut stats = Stats::new();
+        stats.record_clear(ClearType::new(Piece::L, Spin::Mini, 0), false, table);
+        assert_eq!(stats.score, 100);
+        stats.record_clear(ClearType::new(Piece::S, Spin::Mini, 2), false, table);
+        assert_eq!((stats.score, stats.attack), (500, 1));
+        assert_eq!(stats.clear_counts.tsd, 0);
+    }
+
+    #[test]
+    fn perfect_clears() {
+        let mut stats = Stats::new();
+        let table = AttackTable::Guideline;
+        stats.record_clear(ClearType::new(Piece::I, Spin::None, 2), true, table);
+        assert_eq!((stats.score, stats.attack), (1500, 11));
+        // A back-to-back quad PC scores the bigger bonus
+        let mut stats = Stats::new();
+        stats.record_clear(ClearType::new(Piece::I, Spin::None, 4), false, table);
+        stats.record_clear(ClearType::new(Piece::I, Spin::None, 4), true, table);
+        assert_eq!((stats.score, stats.attack), (800 + 4450, 4 + 16));
+        assert_eq!(stats.clear_counts.perfect_clears, 1);
     }
 }
@@ -1,29 +1,31 @@
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
-use crossbeam_channel::unbounded;
 use macroquad::prelude::*;
-use tetris::state::Game;
-use tetris::logic::{Config, KeyAction, Stats};
+use tetris::logic::{Key, KeyAction, Settings};
 use tetris::ui::*;
 
 #[macroquad::main("Tetris Program")]
 async fn main() {
     let font = load_ttf_font("res/font.ttf").await.unwrap();
-    let mut game = Game::new();
-    let mut config = Config::default();
-    let mut stats = Stats::new();
+    let mut settings = Settings::load();
+    let mut config = settings.config();
+    let mut frontend = Frontend::new(&config);
+    // Pick up where the last session left off if the seed is pinned
+    if let Some(seed) = settings.last_seed().filter(|_| config.pin_seed) {
+        frontend.restart(seed, config.create_randomizer());
+    }
     let mut waiting_for_keybind: Option<KeyAction> = None;
-    let mut keycode_set: Option<KeyCode> = None;
+    let mut keycode_set: Option<Key> = None;
     let mut waiting = false;
-    let (tx, rx) = unbounded();
-    let mut cancel_flag: Option<Arc<AtomicBool>> = None;
+    let mut inputs = TextInputs::new();
 
     loop {
         clear_background(BLACK);
-        draw_ui(&mut config, &mut waiting_for_keybind, &mut waiting);
+        let typing = draw_ui(&mut config, &mut settings, &mut frontend, &mut inputs, &mut waiting_for_keybind,
+                &mut waiting);
         wait_for_keybind(&mut config, &mut waiting_for_keybind, &mut keycode_set);
-        game.step(&config, &mut stats, waiting, &mut cancel_flag, &rx, &tx);
-        game.draw(font, &stats);
+        settings.update(&config);
+        settings.update_seed(frontend.game.seed);
+        frontend.step(&config, waiting || typing);
+        frontend.draw(&config, font);
         check_done_waiting(&mut waiting, &mut keycode_set);
         next_frame().await
     }
@@ -0,0 +1,77 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::state::Piece;
+
+// One part of a pattern -- some number of different pieces out of a set, in any order. A single
+// piece like "T" is a set of one.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PatternElement {
+    choices: Vec<Piece>,
+    count: usize,
+}
+
+impl PatternElement {
+    pub fn new(choices: Vec<Piece>, count: usize) -> Result<Self, &'static str> {
+        if choices.is_empty() {
+            return Err("A piece set is empty");
+        }
+        if choices.iter().enumerate().any(|(i, piece)| choices[..i].contains(piece)) {
+            return Err("A piece is listed twice in a set");
+        }
+        if count == 0 || count > choices.len() {
+            return Err("Can't draw more pieces than the set has");
+        }
+        Ok(Self {
+            choices,
+            count,
+        })
+    }
+
+    pub fn fixed(piece: Piece) -> Self {
+        Self {
+            choices: vec![piece],
+            count: 1,
+        }
+    }
+
+    pub fn choices(&self) -> &[Piece] {
+        &self.choices
+    }
+
+    // Number of pieces this part of the pattern deals
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // Number of different orders this can come out in, or None if there are too many to count
+    pub fn sequence_count(&self) -> Option<usize> {
+        (self.choices.len() - self.count + 1..=self.choices.len()).try_fold(1usize, |count, n| count.checked_mul(n))
+    }
+
+    // Every order this can come out in
+    pub fn sequences(&self) -> Vec<Vec<Piece>> {
+        fn permutations(choices: &[Piece], count: usize) -> Vec<Vec<Piece>> {
+            if count == 0 {
+                return vec![Vec::new()];
+            }
+            let mut sequences: Vec<Vec<Piece>> = Vec::new();
+            for (i, &piece) in choices.iter().enumerate() {
+                let mut rest = choices.to_vec();
+                rest.remove(i);
+                for tail in permutations(&rest, count - 1) {
+                    let mut sequence = vec![piece];
+                    sequence.extend(tail);
+                    sequences.push(sequence);
+                }
+            }
+            sequences
+        }
+        permutations(&self.choices, self.count)
+    }
+
+    // One random order, all equally likely
+    pub fn draw(&self, rng: &mut StdRng) -> Vec<Piece> {
+        let mut choices = self.choices.clone();
+        (0..self.count).map(|_| choices.swap_remove(rng.random_range(0..choices.len()))).collect()
+    }
+}
@@ -0,0 +1,7 @@
+mod element;
+mod parse;
+mod queue_pattern;
+
+pub use element::*;
+pub use queue_pattern::*;
+use parse::*;
@@ -0,0 +1,126 @@
+use crate::rng::ALL_PIECES;
+use crate::state::Piece;
+use super::PatternElement;
+
+// Reads sfinder-style queue patterns:
+// - "T" for a single piece, so "TIJ" is three pieces in that order
+// - "*" for any one piece, and "[IOT]" for one out of the listed pieces
+// - "[^IOT]" for one out of every piece except the listed ones
+// - a "pN" after a set to draw N different pieces from it, e.g. "*p7" for a whole bag, or "!" to
+//   draw every piece in it
+// Parts can be separated by commas, and spaces are ignored.
+pub fn parse_pattern(text: &str) -> Result<Vec<PatternElement>, &'static str> {
+    let mut elements: Vec<PatternElement> = Vec::new();
+    let mut chars = text.chars().filter(|c| !c.is_whitespace()).peekable();
+    while let Some(c) = chars.next() {
+        let choices = match c {
+            ',' => continue,
+            '*' => ALL_PIECES.to_vec(),
+            '[' => {
+                let negated = chars.next_if_eq(&'^').is_some();
+                let mut listed: Vec<Piece> = Vec::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(c) => {
+                            let piece = Piece::from_char(c).ok_or("Unknown piece inside [...]")?;
+                            if listed.contains(&piece) {
+                                return Err("A piece is listed twice inside [...]");
+                            }
+                            listed.push(piece);
+                        },
+                        None => return Err("Missing ]"),
+                    }
+                }
+                if negated {
+                    ALL_PIECES.iter().copied().filter(|piece| !listed.contains(piece)).collect()
+                } else {
+                    listed
+                }
+            },
+            _ => {
+                let piece = Piece::from_char(c).ok_or("Pieces have to be one of IJLOSTZ")?;
+                elements.push(PatternElement::fixed(piece));
+                continue;
+            },
+        };
+        // How many to draw, e.g. the 7 in "*p7"
+        let count = if chars.next_if(|&c| c == 'p' || c == 'P').is_some() {
+            let mut digits = String::new();
+            while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                digits.push(digit);
+            }
+            digits.parse().map_err(|_| "Missing number after p")?
+        } else if chars.next_if_eq(&'!').is_some() {
+            choices.len()
+        } else {
+            1
+        };
+        elements.push(PatternElement::new(choices, count)?);
+    }
+    if elements.is_empty() {
+        return Err("The pattern is empty");
+    }
+    Ok(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Piece::*;
+
+    fn element(choices: &[Piece], count: usize) -> PatternElement {
+        PatternElement::new(choices.to_vec(), count).unwrap()
+    }
+
+    #[test]
+    fn whole_bag() {
+        assert_eq!(parse_pattern("*p7"), Ok(vec![element(&ALL_PIECES, 7)]));
+        assert_eq!(parse_pattern("*!"), Ok(vec![element(&ALL_PIECES, 7)]));
+        assert_eq!(parse_pattern("*"), Ok(vec![element(&ALL_PIECES, 1)]));
+    }
+
+    #[test]
+    fn negated_set() {
+        assert_eq!(parse_pattern("[^TI]p2"), Ok(vec![element(&[J, L, O, S, Z], 2)]));
+        assert_eq!(parse_pattern("[TI]P2"), Ok(vec![element(&[T, I], 2)]));
+    }
+
+    #[test]
+    fn fixed_run() {
+        let expected = vec![PatternElement::fixed(T), PatternElement::fixed(I), PatternElement::fixed(J)];
+        assert_eq!(parse_pattern("TIJ"), Ok(expected.clone()));
+        assert_eq!(parse_pattern("tij"), Ok(expected));
+    }
+
+    #[test]
+    fn comma_separated_parts() {
+        let expected = vec![PatternElement::fixed(T), element(&[I, O], 1), element(&ALL_PIECES, 4)];
+        assert_eq!(parse_pattern("T,[IO],*p4"), Ok(expected.clone()));
+        assert_eq!(parse_pattern(" T , [I O] ,, *p4 "), Ok(expected));
+    }
+
+    #[test]
+    fn bad_counts() {
+        assert!(parse_pattern("*p0").is_err());
+        assert!(parse_pattern("[TI]p3").is_err());
+        assert!(parse_pattern("*p8").is_err());
+        assert_eq!(parse_pattern("*p"), Err("Missing number after p"));
+    }
+
+    #[test]
+    fn empty_sets() {
+        assert_eq!(parse_pattern("[]"), Err("A piece set is empty"));
+        assert_eq!(parse_pattern("[^IJLOSTZ]"), Err("A piece set is empty"));
+        assert_eq!(parse_pattern(""), Err("The pattern is empty"));
+        assert_eq!(parse_pattern(" , "), Err("The pattern is empty"));
+    }
+
+    #[test]
+    fn bad_letters() {
+        assert_eq!(parse_pattern("TX"), Err("Pieces have to be one of IJLOSTZ"));
+        assert_eq!(parse_pattern("[TX]"), Err("Unknown piece inside [...]"));
+        assert_eq!(parse_pattern("[TT]"), Err("A piece is listed twice inside [...]"));
+        assert_eq!(parse_pattern("[TI"), Err("Missing ]"));
+    }
+}
@@ -0,0 +1,137 @@
+use rand::rngs::StdRng;
+use std::collections::VecDeque;
+use crate::state::Piece;
+use super::{parse_pattern, PatternElement};
+
+// A family of queues, written the way sfinder takes them, e.g. "T,*p4" for a T followed by any 4
+// different pieces
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QueuePattern {
+    elements: Vec<PatternElement>,
+}
+
+impl QueuePattern {
+    pub fn parse(text: &str) -> Result<Self, &'static str> {
+        Ok(Self {
+            elements: parse_pattern(text)?,
+        })
+    }
+
+    pub fn elements(&self) -> &[PatternElement] {
+        &self.elements
+    }
+
+    // Number of pieces in each queue
+    pub fn len(&self) -> usize {
+        self.elements.iter().map(|element| element.count()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Number of queues the pattern covers, or None if there are too many to count
+    pub fn sequence_count(&self) -> Option<usize> {
+        self.elements.iter().try_fold(1usize, |count, element| count.checked_mul(element.sequence_count()?))
+    }
+
+    // Goes through every queue the pattern covers, one at a time since there can be a lot of them
+    pub fn sequences(&self) -> Sequences {
+        Sequences::new(self)
+    }
+
+    // One random queue, all equally likely
+    pub fn draw(&self, rng: &mut StdRng) -> VecDeque<Piece> {
+        self.elements.iter().flat_map(|element| element.draw(rng)).collect()
+    }
+
+    // True if the queue (current piece first) starts with one the pattern covers
+    pub fn matches(&self, queue: &[Piece]) -> bool {
+        let mut rest = queue;
+        for element in self.elements.iter() {
+            if rest.len() < element.count() {
+                return false;
+            }
+            let (part, tail) = rest.split_at(element.count());
+            if !part.iter().all(|piece| element.choices().contains(piece))
+                    || part.iter().enumerate().any(|(i, piece)| part[..i].contains(piece)) {
+                return false;
+            }
+            rest = tail;
+        }
+        true
+    }
+}
+
+// Counts through the orders of each element like an odometer, last element fastest
+pub struct Sequences {
+    orders: Vec<Vec<Vec<Piece>>>, // Every order of each element
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl Sequences {
+    fn new(pattern: &QueuePattern) -> Self {
+        let orders: Vec<Vec<Vec<Piece>>> = pattern.elements.iter().map(|element| element.sequences()).collect();
+        Self {
+            indices: vec![0; orders.len()],
+            done: orders.is_empty(),
+            orders,
+        }
+    }
+}
+
+impl Iterator for Sequences {
+    type Item = VecDeque<Piece>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let sequence = self.indices.iter().zip(self.orders.iter())
+            .flat_map(|(&index, orders)| orders[index].iter().copied())
+            .collect();
+        // Move on to the next one
+        self.done = true;
+        for i in (0..self.indices.len()).rev() {
+            self.indices[i] += 1;
+            if self.indices[i] < self.orders[i].len() {
+                self.done = false;
+                break;
+            }
+            self.indices[i] = 0;
+        }
+        Some(sequence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Piece::*;
+
+    #[test]
+    fn counts_queues() {
+        let pattern = QueuePattern::parse("T,[^TI]p2").unwrap();
+        assert_eq!(pattern.len(), 3);
+        assert_eq!(pattern.sequence_count(), Some(20));
+        assert_eq!(pattern.sequences().count(), 20);
+        assert_eq!(QueuePattern::parse("*p7").unwrap().sequence_count(), Some(5040));
+    }
+
+    #[test]
+    fn long_patterns_are_too_many_to_count() {
+        let pattern = QueuePattern::parse("*p7,*p7,*p7,*p7,*p7,*p7").unwrap();
+        assert_eq!(pattern.len(), 42);
+        assert_eq!(pattern.sequence_count(), None);
+    }
+
+    #[test]
+    fn matches_queues() {
+        let pattern = QueuePattern::parse("T,[^TI]p2").unwrap();
+        assert!(pattern.matches(&[T, J, L, I]));
+        assert!(!pattern.matches(&[T, J, J]));
+        assert!(!pattern.matches(&[T, I, L]));
+        assert!(!pattern.matches(&[T, J]));
+    }
+}
@@ -1,24 +1,18 @@
 use rand::rngs::StdRng;
 use rand::SeedableRng;
-use lazy_static::lazy_static;
-use std::sync::{Mutex, MutexGuard};
 
-lazy_static! {
-    static ref RNG: Mutex<StdRng> = {
-        // let seed = 100;
-        // Mutex::new(StdRng::seed_from_u64(seed))
-        Mutex::new(StdRng::from_os_rng())
-    };
+mod queue_randomizer;
+mod randomizer;
+
+pub use queue_randomizer::*;
+pub use randomizer::*;
+
+// Picks a fresh seed for a new game
+pub fn random_seed() -> u64 {
+    rand::random()
 }
 
-pub fn rng<'a>() -> MutexGuard<'a, StdRng> {
-    match RNG.lock() {
-        Ok(rng) => {
-            rng
-        },
-        Err(poisoned) => {
-            eprintln!("Mutex was poisoned!");
-            poisoned.into_inner()
-        },
-    }
+// Every game owns its own generator so that the same seed always reproduces the same queue
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
 }
@@ -0,0 +1,96 @@
+use rand::rngs::StdRng;
+use std::collections::VecDeque;
+use crate::pattern::QueuePattern;
+use crate::state::Piece;
+use super::{BagRandomizer, Randomizer, ALL_PIECES};
+
+// Deals out a typed queue pattern (e.g. "TIJLOSZ" or "*p7"), then either starts it over or
+// carries on with 7-bags
+#[derive(Clone)]
+pub struct QueueRandomizer {
+    pattern: QueuePattern,
+    looping: bool,
+    index: usize, // Next pattern element to deal from
+    drawn: VecDeque<Piece>, // Pieces of the current element that haven't been dealt yet
+    undealt: Vec<Piece>, // Pieces the current element could still deal, as far as the player can tell
+    after: BagRandomizer, // Takes over once a queue that doesn't loop runs out
+}
+
+impl QueueRandomizer {
+    pub fn new(text: &str, looping: bool) -> Result<Self, &'static str> {
+        Ok(Self {
+            pattern: QueuePattern::parse(text)?,
+            looping,
+            index: 0,
+            drawn: VecDeque::new(),
+            undealt: Vec::new(),
+            after: BagRandomizer::new(ALL_PIECES.to_vec(), 0),
+        })
+    }
+
+    fn finished(&self) -> bool {
+        self.drawn.is_empty() && self.index >= self.pattern.elements().len()
+    }
+}
+
+impl Randomizer for QueueRandomizer {
+    fn next_piece(&mut self, rng: &mut StdRng) -> Piece {
+        if self.finished() {
+            if !self.looping {
+                return self.after.next_piece(rng);
+            }
+            self.index = 0;
+        }
+        if self.drawn.is_empty() {
+            let element = &self.pattern.elements()[self.index];
+            self.drawn.extend(element.draw(rng));
+            self.undealt = element.choices().to_vec();
+            self.index += 1;
+        }
+        let piece = self.drawn.pop_front().expect("Pattern element drew no pieces");
+        self.undealt.retain(|&p| p != piece);
+        piece
+    }
+
+    fn known_next(&self) -> Option<Piece> {
+        // Only what can be worked out from the queue as typed, not from pieces already drawn
+        if !self.drawn.is_empty() {
+            return if self.undealt.len() == 1 {
+                Some(self.undealt[0])
+            } else {
+                None
+            };
+        }
+        if self.finished() && !self.looping {
+            return self.after.known_next();
+        }
+        let elements = self.pattern.elements();
+        match elements.get(self.index).or(elements.first()) {
+            Some(element) if element.choices().len() == 1 => Some(element.choices()[0]),
+            _ => None,
+        }
+    }
+
+    fn seven_bag_left(&self) -> Option<Vec<Piece>> {
+        if self.finished() && !self.looping {
+            self.after.seven_bag_left()
+        } else {
+            None
+        }
+    }
+
+    fn fresh(&self) -> Box<dyn Randomizer> {
+        Box::new(Self {
+            pattern: self.pattern.clone(),
+            looping: self.looping,
+            index: 0,
+            drawn: VecDeque::new(),
+            undealt: Vec::new(),
+            after: BagRandomizer::new(ALL_PIECES.to_vec(), 0),
+        })
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
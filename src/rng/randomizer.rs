@@ -0,0 +1,222 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+use crate::state::Piece::{self, *};
+
+pub(crate) const ALL_PIECES: [Piece; 7] = [I, J, L, O, S, T, Z];
+
+// Decides which piece comes next. Randomness always comes from the game's seeded generator so
+// that the same seed reproduces the same queue.
+pub trait Randomizer: Send {
+    fn next_piece(&mut self, rng: &mut StdRng) -> Piece;
+
+    // The next piece, if it can already be deduced from the pieces dealt so far
+    fn known_next(&self) -> Option<Piece>;
+
+    // For randomizers that deal plain 7-bags, the pieces of the current bag that haven't been dealt
+    fn seven_bag_left(&self) -> Option<Vec<Piece>>;
+
+    // A randomizer of the same kind that hasn't dealt anything yet
+    fn fresh(&self) -> Box<dyn Randomizer>;
+
+    fn box_clone(&self) -> Box<dyn Randomizer>;
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum RandomizerKind {
+    SevenBag,
+    FourteenBag,
+    SevenPlusOneBag,
+    Memoryless,
+    TgmHistory,
+}
+
+impl RandomizerKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RandomizerKind::SevenBag => "7-bag",
+            RandomizerKind::FourteenBag => "14-bag",
+            RandomizerKind::SevenPlusOneBag => "7+1 bag",
+            RandomizerKind::Memoryless => "Memoryless",
+            RandomizerKind::TgmHistory => "TGM history",
+        }
+    }
+
+    pub fn create(&self) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::SevenBag => Box::new(BagRandomizer::new(ALL_PIECES.to_vec(), 0)),
+            RandomizerKind::FourteenBag => Box::new(BagRandomizer::new([ALL_PIECES, ALL_PIECES].concat(), 0)),
+            RandomizerKind::SevenPlusOneBag => Box::new(BagRandomizer::new(ALL_PIECES.to_vec(), 1)),
+            RandomizerKind::Memoryless => Box::new(MemorylessRandomizer),
+            RandomizerKind::TgmHistory => Box::new(HistoryRandomizer::new()),
+        }
+    }
+}
+
+// Deals out a whole bag before refilling it. Covers 7-bag and 14-bag, plus 7+1 bag where some
+// number of fully random extras get shuffled in with each bag.
+#[derive(Clone)]
+pub struct BagRandomizer {
+    contents: Vec<Piece>, // Pieces that go into every bag
+    extras: usize, // Number of random extra pieces added to every bag
+    remaining: Vec<Piece>, // Pieces of the current bag that haven't been dealt yet
+    dealt: Vec<Piece>, // Pieces of the current bag that have been dealt
+}
+
+impl BagRandomizer {
+    pub fn new(contents: Vec<Piece>, extras: usize) -> Self {
+        Self {
+            contents,
+            extras,
+            remaining: Vec::new(),
+            dealt: Vec::new(),
+        }
+    }
+}
+
+impl Randomizer for BagRandomizer {
+    fn next_piece(&mut self, rng: &mut StdRng) -> Piece {
+        if self.remaining.is_empty() {
+            self.remaining = self.contents.clone();
+            for _ in 0..self.extras {
+                self.remaining.push(ALL_PIECES[rng.random_range(0..7)]);
+            }
+            self.dealt.clear();
+        }
+        let piece = self.remaining.swap_remove(rng.random_range(0..self.remaining.len()));
+        self.dealt.push(piece);
+        piece
+    }
+
+    fn known_next(&self) -> Option<Piece> {
+        // Work out which pieces must still be left in the bag from its fixed contents -- if that
+        // accounts for everything left, then every extra has already shown up
+        let mut left = self.contents.clone();
+        for piece in self.dealt.iter() {
+            if let Some(index) = left.iter().position(|p| p == piece) {
+                left.swap_remove(index);
+            }
+        }
+        if left.is_empty() || left.len() != self.remaining.len() {
+            return None;
+        }
+        // Only known if every piece left in the bag is the same
+        if left.iter().all(|&p| p == left[0]) {
+            Some(left[0])
+        } else {
+            None
+        }
+    }
+
+    fn seven_bag_left(&self) -> Option<Vec<Piece>> {
+        if self.contents.len() == 7 && self.extras == 0 {
+            Some(self.remaining.clone())
+        } else {
+            None
+        }
+    }
+
+    fn fresh(&self) -> Box<dyn Randomizer> {
+        Box::new(Self::new(self.contents.clone(), self.extras))
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+// Every piece is equally likely every time
+#[derive(Clone)]
+pub struct MemorylessRandomizer;
+
+impl Randomizer for MemorylessRandomizer {
+    fn next_piece(&mut self, rng: &mut StdRng) -> Piece {
+        ALL_PIECES[rng.random_range(0..7)]
+    }
+
+    fn known_next(&self) -> Option<Piece> {
+        None
+    }
+
+    fn seven_bag_left(&self) -> Option<Vec<Piece>> {
+        None
+    }
+
+    fn fresh(&self) -> Box<dyn Randomizer> {
+        Box::new(MemorylessRandomizer)
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+// TGM2-style randomizer -- rerolls up to 6 times to avoid any of the last 4 pieces dealt, and never
+// starts with S, Z or O
+#[derive(Clone)]
+pub struct HistoryRandomizer {
+    history: VecDeque<Piece>,
+    first: bool,
+}
+
+impl HistoryRandomizer {
+    const ROLLS: usize = 6;
+
+    pub fn new() -> Self {
+        Self {
+            history: VecDeque::from(vec![Z, S, S, Z]),
+            first: true,
+        }
+    }
+}
+
+impl Default for HistoryRandomizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Randomizer for HistoryRandomizer {
+    fn next_piece(&mut self, rng: &mut StdRng) -> Piece {
+        let piece = if self.first {
+            self.first = false;
+            [I, J, L, T][rng.random_range(0..4)]
+        } else {
+            let mut piece = ALL_PIECES[rng.random_range(0..7)];
+            for _ in 1..Self::ROLLS {
+                if !self.history.contains(&piece) {
+                    break;
+                }
+                piece = ALL_PIECES[rng.random_range(0..7)];
+            }
+            piece
+        };
+        self.history.pop_front();
+        self.history.push_back(piece);
+        piece
+    }
+
+    fn known_next(&self) -> Option<Piece> {
+        None
+    }
+
+    fn seven_bag_left(&self) -> Option<Vec<Piece>> {
+        None
+    }
+
+    fn fresh(&self) -> Box<dyn Randomizer> {
+        Box::new(Self::new())
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,88 @@
+use crate::state::{Board, Piece, Rotation, Tile};
+
+const FULL_ROW: u16 = (1 << 10) - 1;
+
+// Compact board used by the search code -- each row is a bitmask where bit c is set when column c
+// is filled. Much cheaper to copy and hash than the colored Board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitBoard {
+    pub rows: [u16; 23],
+}
+
+impl BitBoard {
+    pub fn new() -> Self {
+        Self {
+            rows: [0; 23],
+        }
+    }
+
+    pub fn from(board: &Board) -> Self {
+        let mut bit_board = Self::new();
+        for r in 0..23 {
+            for c in 0..10 {
+                if board.tiles[r][c].is_filled() {
+                    bit_board.rows[r] |= 1 << c;
+                }
+            }
+        }
+        bit_board
+    }
+
+    // Converts back to a colored board, using the given tile for every filled cell
+    pub fn to_board(&self, fill: Tile) -> Board {
+        let mut board = Board::new();
+        for r in 0..23 {
+            for c in 0..10 {
+                if self.rows[r] & (1 << c) != 0 {
+                    board.tiles[r][c] = fill;
+                }
+            }
+        }
+        board
+    }
+
+    // Out of bounds cells count as filled so that walls and the floor block movement
+    pub fn is_filled(&self, row: i8, col: i8) -> bool {
+        if !(0..23).contains(&row) || !(0..10).contains(&col) {
+            return true;
+        }
+        self.rows[row as usize] & (1 << col) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows[3..].iter().all(|&row| row == 0)
+    }
+
+    pub fn is_row_full(&self, row: usize) -> bool {
+        self.rows[row] == FULL_ROW
+    }
+
+    pub fn mino_count(&self) -> u32 {
+        self.rows.iter().map(|row| row.count_ones()).sum()
+    }
+
+    pub fn with_placement(&self, piece: Piece, row: u8, col: u8, rotation: Rotation) -> Self {
+        let mut new_board = *self;
+        for &(offset_row, offset_col) in piece.offset_map(rotation).iter() {
+            let r = row as i8 + offset_row;
+            let c = col as i8 + offset_col;
+            new_board.rows[r as usize] |= 1 << c;
+        }
+        new_board
+    }
+
+    // Returns the number of lines cleared
+    pub fn clear_lines(&mut self) -> u8 {
+        let mut new_rows = [0; 23];
+        let mut write = 23;
+        for r in (0..23).rev() {
+            if self.rows[r] == FULL_ROW {
+                continue;
+            }
+            write -= 1;
+            new_rows[write] = self.rows[r];
+        }
+        self.rows = new_rows;
+        write as u8
+    }
+}
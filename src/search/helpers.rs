@@ -3,35 +3,34 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use crossbeam_channel::Sender;
-use crate::logic::{gen_bag, Stats};
+use crate::logic::{detect_spin, ClearType, Spin, Stats};
+use crate::pattern::QueuePattern;
 use crate::setups::*;
-use crate::state::{Board, Game, Piece, Rotation};
-use super::{Movement, Pc, PcState, Placement, SearchState};
+use crate::state::{Board, Game, Piece, Rotation, RotationSystem};
+use super::{BitBoard, Movement, Pc, PcState, Placement, SearchState};
 
 // Returns a set of all possible final locations
-pub fn get_locations(board: &Board, piece: Piece) -> HashSet<SearchState> {
+pub fn get_locations(board: &BitBoard, rotation_system: RotationSystem, piece: Piece) -> HashSet<SearchState> {
     // Set of possible final placements
     let mut locations: HashSet<SearchState> = HashSet::new();
     // Queue of states in search tree
-    let mut q: VecDeque<SearchState> = VecDeque::from(
-        vec![SearchState::new(1, 4, Rotation::Normal, piece)]
-    );
-    let mut visited: HashSet<SearchState> = HashSet::new();
+    let start = SearchState::new(1, 4, Rotation::Normal, piece);
+    let mut q: VecDeque<SearchState> = VecDeque::from(vec![start]);
+    // Indexed by SearchState::index -- much faster than hashing every state
+    let mut visited = [false; SearchState::NUM_INDICES];
+    visited[start.index()] = true;
 
     while let Some(state) = q.pop_front() {
-        if visited.contains(&state) {
-            continue;
-        }
-        visited.insert(state);
         // Add this position if it hasn't been found already
         let dropped = state.drop(board);
         if !locations.contains(&dropped) && !locations.contains(&dropped.symmetrical()) {
             locations.insert(dropped);
         }
-        for &(successor, _) in state.successors(board).iter() {
-            if visited.contains(&successor) {
+        for &(successor, _) in state.successors(board, rotation_system).iter() {
+            if visited[successor.index()] {
                 continue;
             }
+            visited[successor.index()] = true;
             q.push_back(successor);
         }
     }
@@ -39,23 +38,23 @@ pub fn get_locations(board: &Board, piece: Piece) -> HashSet<SearchState> {
 }
 
 // Returns a map of all possible final locations to the optimal sequence of moves to place it there
-pub fn get_locations_with_finesse(board: &Board, piece: Piece) -> HashMap<SearchState, Vec<Movement>> {
+pub fn get_locations_with_finesse(
+    board: &BitBoard,
+    rotation_system: RotationSystem,
+    piece: Piece,
+) -> HashMap<SearchState, Vec<Movement>> {
     // Map of possible final placements
     let mut locations: HashMap<SearchState, Vec<Movement>> = HashMap::new();
     // Queue of (state, prev_nodes index) states in search tree
-    let mut q: VecDeque<(SearchState, usize)> = VecDeque::from(
-        vec![(SearchState::new(1, 4, Rotation::Normal, piece), 0)]
-    );
-    let mut visited: HashSet<SearchState> = HashSet::new();
+    let start = SearchState::new(1, 4, Rotation::Normal, piece);
+    let mut q: VecDeque<(SearchState, usize)> = VecDeque::from(vec![(start, 0)]);
+    let mut visited = [false; SearchState::NUM_INDICES];
+    visited[start.index()] = true;
     // List of all already-visited nodes for reconstructing optimal paths at the end
     // Holds tuples of (action taken to get to this state, index of parent state)
     let mut prev_nodes: Vec<(Option<Movement>, usize)> = vec![(None, 0)];
 
     while let Some((state, index)) = q.pop_front() {
-        if visited.contains(&state) {
-            continue;
-        }
-        visited.insert(state);
         // Add this position if it hasn't been found already
         let dropped = state.drop(board);
         if !locations.contains_key(&dropped) {
@@ -73,10 +72,11 @@ pub fn get_locations_with_finesse(board: &Board, piece: Piece) -> HashMap<Search
             locations.insert(dropped, Vec::from(path.clone()));
             locations.insert(dropped.symmetrical(), Vec::from(path));
         }
-        for &(successor, action) in state.successors(board).iter() {
-            if visited.contains(&successor) {
+        for &(successor, action) in state.successors(board, rotation_system).iter() {
+            if visited[successor.index()] {
                 continue;
             }
+            visited[successor.index()] = true;
             prev_nodes.push((Some(action), index));
             q.push_back((successor, prev_nodes.len() - 1));
         }
@@ -86,14 +86,15 @@ pub fn get_locations_with_finesse(board: &Board, piece: Piece) -> HashMap<Search
 
 // Returns minimum number of moves for a certain placement
 pub fn get_finesse_faults(
-    board: &Board,
+    board: &BitBoard,
+    rotation_system: RotationSystem,
     piece: Piece,
     moves: u8,
     row: u8,
     col: u8,
     rotation: Rotation,
 ) -> (u8, Option<Vec<Movement>>) {
-    let location_map = get_locations_with_finesse(board, piece);
+    let location_map = get_locations_with_finesse(board, rotation_system, piece);
     let target_state = SearchState::new(row as i8, col as i8, rotation, piece);
     match location_map.get(&target_state) {
         Some(path) => {
@@ -108,12 +109,119 @@ pub fn get_finesse_faults(
     }
 }
 
-pub fn find_pcs(game: Game, tx: Sender<Vec<Pc>>) -> Arc<AtomicBool> {
+// Returns the best spin a piece could get by locking at the target position, assuming the player
+// rotates into it last with whichever kick counts for the most
+pub fn get_best_spin(board: &BitBoard, rotation_system: RotationSystem, target: SearchState, all_spins: bool) -> Spin {
+    let piece = target.piece;
+    if piece == Piece::O || (piece != Piece::T && !all_spins) {
+        return Spin::None;
+    }
+    // Even a TST kick wouldn't make it a spin, so there's no need to look for a way in
+    let (row, col, rotation) = (target.row, target.col, target.rotation);
+    if detect_spin(board, piece, row, col, rotation, Some((2, 1)), all_spins) == Spin::None {
+        return Spin::None;
+    }
+    let targets = [target, target.symmetrical()];
+    let start = SearchState::new(1, 4, Rotation::Normal, piece);
+    let mut q: VecDeque<SearchState> = VecDeque::from(vec![start]);
+    let mut visited = [false; SearchState::NUM_INDICES];
+    visited[start.index()] = true;
+    let mut best = Spin::None;
+
+    while let Some(state) = q.pop_front() {
+        // Check whether rotating from here ends up exactly on the target
+        for new_rot in [state.rotation.cw(), state.rotation.ccw(), state.rotation.flip()] {
+            let kicked = rotation_system.rotate(piece, state.row, state.col, state.rotation, new_rot, |row, col| {
+                !SearchState::new(row, col, new_rot, piece).intersects(board)
+            });
+            if let Some((row, col, kick_index)) = kicked {
+                if targets.contains(&SearchState::new(row, col, new_rot, piece)) {
+                    let kick = rotation_system.kicks(piece, state.rotation, new_rot)[kick_index];
+                    best = best.max(detect_spin(board, piece, row, col, new_rot, Some(kick), all_spins));
+                }
+            }
+        }
+        for &(successor, _) in state.successors(board, rotation_system).iter() {
+            if visited[successor.index()] {
+                continue;
+            }
+            visited[successor.index()] = true;
+            q.push_back(successor);
+        }
+    }
+    best
+}
+
+// Plays out a sequence of placements from the board and returns the clear type of each one. Solutions
+// to the same position share a lot of placements, so `spins` keeps the best spin for each board and
+// placement already looked at.
+pub fn get_clears(
+    board: &Board,
+    placements: &[Placement],
+    rotation_system: RotationSystem,
+    all_spins: bool,
+    spins: &mut HashMap<(BitBoard, SearchState), Spin>,
+) -> Vec<ClearType> {
+    let mut board = BitBoard::from(board);
+    let mut clears: Vec<ClearType> = Vec::new();
+    for &placement in placements.iter() {
+        if let Placement::Place { piece, row, col, rotation } = placement {
+            let target = SearchState::new(row as i8, col as i8, rotation, piece);
+            let spin = *spins.entry((board, target))
+                .or_insert_with(|| get_best_spin(&board, rotation_system, target, all_spins));
+            board = board.with_placement(piece, row, col, rotation);
+            let lines = board.clear_lines();
+            clears.push(ClearType::new(piece, spin, lines));
+        }
+    }
+    clears
+}
+
+// Returns true if the pieces carried out of a finished PC -- the hold piece plus whatever is left of
+// the bag the next piece comes from -- have a duplicate. That throws off the bag alignment that the
+// PC loop relies on, e.g. looping back to 1st PC with a repeated piece instead of a fresh bag.
+fn breaks_bag_loop(game: &Game, state: &PcState) -> bool {
+    let (left, hold) = match (game.randomizer.seven_bag_left(), state.hold) {
+        (Some(left), Some(hold)) => (left, hold),
+        _ => return false,
+    };
+    let queue_len = game.queue.len() as i32;
+    // Queue index of the first unused piece, where -1 is the piece that was current
+    let next = state.queue_used as i32 - 1;
+    if next >= queue_len {
+        // Somewhere in the bag that hasn't been seen yet, so there's no telling
+        return false;
+    }
+    // Every 7 pieces back from the bag currently being dealt starts another bag
+    let current_bag_start = queue_len - (7 - left.len() as i32);
+    let next_bag_start = current_bag_start + 7 * ((next - current_bag_start).div_euclid(7) + 1);
+
+    let mut carried = vec![hold];
+    for i in next..next_bag_start.min(queue_len) {
+        if i < 0 {
+            carried.extend(game.piece);
+        } else {
+            carried.push(game.queue[i as usize]);
+        }
+    }
+    if next_bag_start >= queue_len {
+        carried.extend(left);
+    }
+    carried.sort();
+    carried.windows(2).any(|pair| pair[0] == pair[1])
+}
+
+pub fn find_pcs(
+    game: Game,
+    rotation_system: RotationSystem,
+    all_spins: bool,
+    tx: Sender<Vec<Pc>>,
+) -> Arc<AtomicBool> {
     let cancel_flag = Arc::new(AtomicBool::new(false));
     let cloned_flag = cancel_flag.clone();
 
     thread::spawn(move || {
-        if let Some(pcs) = find_pcs_helper(&game, cloned_flag) {
+        if let Some(pcs) = find_pcs_helper(&game, rotation_system, all_spins, cloned_flag) {
             tx.send(pcs).unwrap();
         } else {
             return;
@@ -123,45 +231,60 @@ pub fn find_pcs(game: Game, tx: Sender<Vec<Pc>>) -> Arc<AtomicBool> {
 }
 
 // Returns vec of all PC solves it can find from current position and queue
-fn find_pcs_helper(game: &Game, cancel_flag: Arc<AtomicBool>) -> Option<Vec<Pc>> {
-    // First, check if we should even search at all
+pub fn find_pcs_helper(
+    game: &Game,
+    rotation_system: RotationSystem,
+    all_spins: bool,
+    cancel_flag: Arc<AtomicBool>,
+) -> Option<Vec<Pc>> {
     // Try height 1 through 4
-    let initial_state1 = PcState::from(game, 1);
-    let initial_state2 = PcState::from(game, 2);
-    let initial_state3 = PcState::from(game, 3);
-    let initial_state4 = PcState::from(game, 4);
-
+    let initial_states = (1..=4).map(|height| PcState::from(game, height)).collect();
     let mut queue = Vec::from(game.queue.clone());
     while queue.len() > 5 {
         queue.pop();
     }
-    if initial_state1.fails_early(&queue)
-            && initial_state2.fails_early(&queue)
-            && initial_state3.fails_early(&queue)
-            && initial_state4.fails_early(&queue) {
-        return Some(Vec::new());
-    }
 
     let mut solves: Vec<Pc> = Vec::new();
-    // Start search from all 4 height possiblities (consider up through 4L PC)
-    let mut stack: Vec<(PcState, usize)> = vec![
-        (initial_state1, 0),
-        (initial_state2, 1),
-        (initial_state3, 2),
-        (initial_state4, 3),
-    ];
-    let mut visited: HashSet<PcState> = HashSet::new();
+    let mut spins: HashMap<(BitBoard, SearchState), Spin> = HashMap::new();
+    let finished = search_pcs(initial_states, &queue, rotation_system, &cancel_flag, |state, placements| {
+        let clears = get_clears(&game.board, &placements, rotation_system, all_spins, &mut spins);
+        solves.push(Pc::new(game.board, placements, clears, breaks_bag_loop(game, state)));
+        true
+    });
+    if finished {
+        Some(solves)
+    } else {
+        None
+    }
+}
+
+// Searches for PCs starting from any of the initial states, calling `on_solve` with the placements
+// for each one found. The search stops early if `on_solve` returns false. Returns false if it was
+// cancelled before finishing.
+pub fn search_pcs(
+    initial_states: Vec<PcState>,
+    queue: &[Piece],
+    rotation_system: RotationSystem,
+    cancel_flag: &AtomicBool,
+    mut on_solve: impl FnMut(&PcState, Vec<Placement>) -> bool,
+) -> bool {
+    // First, check if we should even search at all
+    if initial_states.iter().all(|state| state.fails_early(queue)) {
+        return true;
+    }
+
+    let mut stack: Vec<(PcState, usize)> = Vec::new();
     // Stores index of previous state in this vector for reconstructing path at the end
-    let mut prev_nodes: Vec<(Option<Placement>, usize)> = vec![
-        (None, 0),
-        (None, 0),
-        (None, 0),
-        (None, 0),
-    ];
+    let mut prev_nodes: Vec<(Option<Placement>, usize)> = Vec::new();
+    for state in initial_states.into_iter() {
+        stack.push((state, prev_nodes.len()));
+        prev_nodes.push((None, 0));
+    }
+    let mut visited: HashSet<PcState> = HashSet::new();
 
     while let Some((state, index)) = stack.pop() {
         if cancel_flag.load(Ordering::Relaxed) {
-            return None;
+            return false;
         }
         if visited.contains(&state) {
             continue;
@@ -180,10 +303,12 @@ fn find_pcs_helper(game: &Game, cancel_flag: Arc<AtomicBool>) -> Option<Vec<Pc>>
                     break;
                 }
             }
-            solves.push(Pc::new(game.board, Vec::from(path)));
+            if !on_solve(&state, Vec::from(path)) {
+                return true;
+            }
         }
 
-        for &(successor, placement) in state.successors(&queue).iter() {
+        for &(successor, placement) in state.successors(queue, rotation_system).iter() {
             if visited.contains(&successor) {
                 continue;
             }
@@ -191,10 +316,19 @@ fn find_pcs_helper(game: &Game, cancel_flag: Arc<AtomicBool>) -> Option<Vec<Pc>>
             stack.push((successor, prev_nodes.len() - 1));
         }
     }
-    Some(solves)
+    true
 }
 
-fn add_setups(setups: &mut HashSet<PcSetup>, piece_limit: usize, setup_list: &Vec<PcSetup>, game: &mut Game, stats: &Stats) {
+// Returns false if cancelled partway through
+fn add_setups(
+    setups: &mut HashSet<PcSetup>,
+    piece_limit: usize,
+    setup_list: &[PcSetup],
+    game: &Game,
+    stats: &Stats,
+    rotation_system: RotationSystem,
+    cancel_flag: &AtomicBool,
+) -> bool {
     // First, add in current piece and hold piece to make setting proper queue length easier
     let mut full_queue = game.queue.clone();
     if let Some(piece) = game.hold {
@@ -203,47 +337,161 @@ fn add_setups(setups: &mut HashSet<PcSetup>, piece_limit: usize, setup_list: &Ve
     if let Some(piece) = game.piece {
         full_queue.push_front(piece);
     }
-    // If we have 6/7 pieces from the bag, we can tell what the last one is
-    // The 6 comes from 5 pieces in queue, one currently about to place
-    if (stats.pieces + 6 + if game.hold.is_some() { 1 } else { 0 }) % 7 == 6 {
-        if game.bag.len() == 0 {
-            gen_bag(game);
-        }
-        full_queue.push_back(*game.bag.front().unwrap());
+    // If the randomizer lets us tell what comes after the queue (e.g. the last piece of a 7-bag
+    // once the other 6 have been seen), we can plan with it too
+    if let Some(piece) = game.randomizer.known_next() {
+        full_queue.push_back(piece);
     }
     // Adjust so that we won't use anything beyond the piece limit
-    // e.g. don't want to use more than first 4 pieces for a 2nd PC setup
-    while full_queue.len() > piece_limit - (stats.pieces - game.pc_piece_num) as usize {
+    // e.g. don't want to use more than first 4 pieces for a 2nd PC setup. A hand-made board can
+    // already hold more pieces than that.
+    let piece_limit = piece_limit.saturating_sub((stats.pieces - game.pc_piece_num) as usize);
+    while full_queue.len() > piece_limit {
         full_queue.pop_back();
     }
     let piece = full_queue.pop_front();
     for setup in setup_list.iter() {
-        if setup.can_build(&game.board, full_queue.clone(), piece, None, game.held) {
+        if cancel_flag.load(Ordering::Relaxed) {
+            return false;
+        }
+        if setup.meets_prerequisites(game) && setup.can_build(&game.board, rotation_system, full_queue.clone(), piece, None, game.held) {
             setups.insert(setup.clone());
         }
     }
+    true
+}
+
+// Runs the setup finder on another thread, sending back the buildable setups unless cancelled
+pub fn find_setups(
+    library: Arc<SetupLibrary>,
+    game: Game,
+    stats: Stats,
+    rotation_system: RotationSystem,
+    tx: Sender<Vec<PcSetup>>,
+) -> Arc<AtomicBool> {
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let cloned_flag = cancel_flag.clone();
+
+    thread::spawn(move || {
+        if let Some(setups) = find_setups_helper(&library, &game, &stats, rotation_system, &cloned_flag) {
+            // The receiver is gone if a newer search replaced this one
+            let _ = tx.send(setups);
+        }
+    });
+    cancel_flag
+}
+
+// Which setups apply to a PC starting after the given number of pieces, along with how many pieces
+// each setup can use
+pub fn setup_pcs(pc_piece_num: u32) -> Vec<(usize, PcNumber)> {
+    match pc_piece_num % 7 + 1 {
+        1 => vec![(7, PcNumber::First)],
+        2 => vec![(6, PcNumber::Sixth)],
+        3 => vec![(5, PcNumber::Fourth)],
+        4 => vec![(4, PcNumber::Second)],
+        5 => vec![(3, PcNumber::Seventh)],
+        6 => vec![(2, PcNumber::Fifth)],
+        7 => vec![(8, PcNumber::Third), (8, PcNumber::Dpc)],
+        _ => Vec::new(),
+    }
 }
 
-pub fn find_setups(game: &mut Game, stats: &Stats) -> Vec<PcSetup> {
+// Works out how many of the pattern's queues can build each setup for the current PC on another
+// thread, sending back (name, built, total) for each setup as soon as it's done
+pub fn find_setup_coverage(
+    library: Arc<SetupLibrary>,
+    game: Game,
+    stats: Stats,
+    pattern: QueuePattern,
+    rotation_system: RotationSystem,
+    tx: Sender<(String, usize, usize)>,
+) -> Arc<AtomicBool> {
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let cloned_flag = cancel_flag.clone();
+
+    thread::spawn(move || {
+        for (piece_limit, pc) in setup_pcs(game.pc_piece_num) {
+            // Pieces already placed toward the PC are used up
+            let piece_limit = piece_limit.saturating_sub((stats.pieces - game.pc_piece_num) as usize);
+            for setup in library.get(pc).iter() {
+                let Some((built, total)) = setup.coverage(&game.board, rotation_system, &pattern, piece_limit,
+                        &cloned_flag) else {
+                    return;
+                };
+                if tx.send((setup.name.clone(), built, total)).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    cancel_flag
+}
+
+pub fn find_setups_helper(
+    library: &SetupLibrary,
+    game: &Game,
+    stats: &Stats,
+    rotation_system: RotationSystem,
+    cancel_flag: &AtomicBool,
+) -> Option<Vec<PcSetup>> {
     // Use a hashset to remove duplicates
     let mut setups: HashSet<PcSetup> = HashSet::new();
-    let piece_num = game.pc_piece_num % 7 + 1;
-
-    match piece_num {
-        1 => add_setups(&mut setups, 7, &FIRST_PCS, game, stats),
-        2 => {},
-        3 => add_setups(&mut setups, 5, &FOURTH_PCS, game, stats),
-        4 => add_setups(&mut setups, 4, &SECOND_PCS, game, stats),
-        5 => {},
-        6 => add_setups(&mut setups, 2, &FIFTH_PCS, game, stats),
-        7 => {
-            add_setups(&mut setups, 8, &THIRD_PCS, game, stats);
-            add_setups(&mut setups, 8, &DPCS, game, stats);
-        },
-        _ => {},
+    for (piece_limit, pc) in setup_pcs(game.pc_piece_num) {
+        if !add_setups(&mut setups, piece_limit, library.get(pc), game, stats, rotation_system, cancel_flag) {
+            return None;
+        }
     }
     // Collect as a sorted vec
     let mut setup_list = setups.iter().cloned().collect::<Vec<PcSetup>>();
     setup_list.sort();
-    setup_list
+    Some(setup_list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Tile;
+
+    // TSD slot on the left, with the overhang on its left side
+    fn tsd_slot() -> Board {
+        let mut board = Board::new();
+        let rows = [
+            "X.........",
+            "...XXXXXXX",
+            "X.XXXXXXXX",
+        ];
+        for (i, row) in rows.iter().enumerate() {
+            for (c, ch) in row.chars().enumerate() {
+                if ch == 'X' {
+                    board.tiles[20 + i][c] = Tile::from(Piece::I);
+                }
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn finds_spins_into_slots() {
+        let board = BitBoard::from(&tsd_slot());
+        let tsd = SearchState::new(21, 1, Rotation::Flip, Piece::T);
+        assert_eq!(get_best_spin(&board, RotationSystem::Srs, tsd, false), Spin::Full);
+        // The same T dropped flat on the floor isn't a spin
+        let flat = SearchState::new(19, 5, Rotation::Normal, Piece::T);
+        assert_eq!(get_best_spin(&board, RotationSystem::Srs, flat, false), Spin::None);
+    }
+
+    #[test]
+    fn clears_reuse_worked_out_spins() {
+        let board = tsd_slot();
+        let placements = [Placement::place(Piece::T, 21, 1, Rotation::Flip), Placement::Hold];
+        let mut spins: HashMap<(BitBoard, SearchState), Spin> = HashMap::new();
+        let clears = get_clears(&board, &placements, RotationSystem::Srs, false, &mut spins);
+        assert_eq!(clears, vec![ClearType::new(Piece::T, Spin::Full, 2)]);
+        assert_eq!(spins.len(), 1);
+        // A cached answer is used as is
+        let key = (BitBoard::from(&board), SearchState::new(21, 1, Rotation::Flip, Piece::T));
+        spins.insert(key, Spin::Mini);
+        let clears = get_clears(&board, &placements, RotationSystem::Srs, false, &mut spins);
+        assert_eq!(clears, vec![ClearType::new(Piece::T, Spin::Mini, 2)]);
+    }
 }
@@ -1,15 +1,19 @@
+mod bit_board;
 mod dsu;
 mod helpers;
 mod movement;
 mod pc;
+mod pc_chance;
 mod pc_state;
 mod placement;
 mod search_state;
 
+pub use bit_board::*;
 pub use dsu::*;
 pub use helpers::*;
 pub use movement::*;
 pub use pc::*;
+pub use pc_chance::*;
 pub use pc_state::*;
 pub use placement::*;
 pub use search_state::*;
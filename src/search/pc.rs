@@ -1,28 +1,104 @@
+use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
-use macroquad::prelude::*;
-use crate::util::window::*;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+use crate::fumen::{encode, FumenPage};
+use crate::logic::{AttackTable, ClearType, Stats};
 use crate::state::{Board, Piece};
 use super::Placement;
 
+// What the PC panel puts first when there are several ways to PC
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+pub enum PcObjective {
+    LowestHeight,
+    MaxAttack,
+    FewestHolds,
+    TSpinFirst,
+}
+
+impl PcObjective {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PcObjective::LowestHeight => "Lowest height",
+            PcObjective::MaxAttack => "Max attack",
+            PcObjective::FewestHolds => "Fewest holds",
+            PcObjective::TSpinFirst => "T-spins first",
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Pc {
     board: Board,
     placements: Vec<Placement>,
+    clears: Vec<ClearType>, // One for each placement, in order
+    breaks_loop: bool, // True when it leaves a duplicate piece across the bag boundary
 }
 
 impl Pc {
-    pub fn new(board: Board, placements: Vec<Placement>) -> Self {
+    pub fn new(board: Board, placements: Vec<Placement>, clears: Vec<ClearType>, breaks_loop: bool) -> Self {
         Self {
             board,
             placements,
+            clears,
+            breaks_loop,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn placements(&self) -> &[Placement] {
+        &self.placements
+    }
+
+    pub fn breaks_loop(&self) -> bool {
+        self.breaks_loop
+    }
+
+    pub fn clears(&self) -> &[ClearType] {
+        &self.clears
+    }
+
+    pub fn holds(&self) -> usize {
+        self.placements.iter().filter(|&&placement| placement == Placement::Hold).count()
+    }
+
+    // Number of T-spins that clear lines along the way
+    pub fn tspins(&self) -> usize {
+        self.clears.iter().filter(|clear| clear.piece == Piece::T && clear.is_spin() && clear.lines > 0).count()
+    }
+
+    // Stats after playing out this PC, continuing any combo and back-to-back from the given stats
+    pub fn stats_after(&self, stats: &Stats, attack_table: AttackTable) -> Stats {
+        let mut stats = *stats;
+        for (i, &clear) in self.clears.iter().enumerate() {
+            stats.record_clear(clear, i + 1 == self.clears.len(), attack_table);
         }
+        stats
+    }
+
+    pub fn attack(&self, stats: &Stats, attack_table: AttackTable) -> u32 {
+        self.stats_after(stats, attack_table).attack - stats.attack
+    }
+
+    pub fn score(&self, stats: &Stats, attack_table: AttackTable) -> u32 {
+        self.stats_after(stats, attack_table).score - stats.score
+    }
+
+    // e.g. "TSD, Double, PC"
+    pub fn summary(&self) -> String {
+        let mut labels: Vec<String> = self.clears.iter().filter_map(|clear| clear.label()).collect();
+        labels.push(String::from("PC"));
+        labels.join(", ")
     }
 
     pub fn height(&self) -> u8 {
         let mut mino_count = 0;
         for r in 3..23 {
             for c in 0..10 {
-                if self.board.tiles[r][c].piece.is_some() {
+                if self.board.tiles[r][c].is_filled() {
                     mino_count += 1;
                 }
             }
@@ -35,7 +111,7 @@ impl Pc {
         (mino_count / 10) as u8
     }
 
-    fn get_final_board(&self) -> (Board, usize) {
+    pub fn get_final_board(&self) -> (Board, usize) {
         // Create a board containing all pieces in the PC
         let mut final_board = Board::new();
         let mut temp_board = self.board;
@@ -49,7 +125,7 @@ impl Pc {
                 // Check if this cleared any lines -- if so, add to final_board
                 'row: for r in cleared..23 {
                     for c in 0..10 {
-                        if temp_board.tiles[r][c].piece.is_none() {
+                        if !temp_board.tiles[r][c].is_filled() {
                             continue 'row;
                         }
                     }
@@ -68,84 +144,19 @@ impl Pc {
         (final_board, cleared)
     }
 
-    // Returns the total height of the drawn PC
-    pub fn draw(&self, x: f32, y: f32, scale: f32) -> f32 {
-        let (final_board, cleared) = self.get_final_board();
-        // Draw the board
-        for r in (23 - cleared)..23 {
-            for c in 0..10 {
-                // If the piece has already been placed, draw as gray to make solution easier to read
-                if self.board.tiles[r][c].piece.is_some() {
-                    draw_rectangle(
-                        x + tile_size() * scale * c as f32,
-                        y + tile_size() * scale * (r - (23 - cleared)) as f32,
-                        tile_size() * scale,
-                        tile_size() * scale,
-                        GRAY,
-                    );
-                    continue;
-                }
-                draw_rectangle(
-                    x + tile_size() * scale * c as f32,
-                    y + tile_size() * scale * (r - (23 - cleared)) as f32,
-                    tile_size() * scale,
-                    tile_size() * scale,
-                    final_board.tiles[r][c].color(),
-                );
-            }
-        }
-        cleared as f32 * tile_size() * scale
-    }
-
-    // Draws the order that the pieces are placed, returns height of drawing
-    pub fn draw_sequence(&self, x: f32, y: f32, scale: f32) -> f32 {
-        let tile = tile_size() * scale;
-        let margin = tile_size() * 0.25;
-        let mut width: f32 = margin;
-
+    // One fumen page per piece placed, starting from the board the PC was found on
+    pub fn to_fumen(&self) -> String {
+        let summary = self.summary();
+        let mut pages: Vec<FumenPage> = Vec::new();
+        let mut board = self.board;
         for &placement in self.placements.iter() {
-            match placement {
-                Placement::Hold => {},
-                Placement::Place { piece, .. } => {
-                    match piece {
-                        Piece::I => {
-                            draw_rectangle(x + width, y + 0.5 * tile, 4.0 * tile, tile, Piece::I.color());
-                            width += 4.0 * tile + margin;
-                        },
-                        Piece::J => {
-                            draw_rectangle(x + width, y, tile, tile, Piece::J.color());
-                            draw_rectangle(x + width, y + tile, 3.0 * tile, tile, Piece::J.color());
-                            width += 3.0 * tile + margin;
-                        },
-                        Piece::L => {
-                            draw_rectangle(x + width + 2.0 * tile, y, tile, tile, Piece::L.color());
-                            draw_rectangle(x + width, y + tile, 3.0 * tile, tile, Piece::L.color());
-                            width += 3.0 * tile + margin;
-                        },
-                        Piece::O => {
-                            draw_rectangle(x + width, y, 2.0 * tile, 2.0 * tile, Piece::O.color());
-                            width += 2.0 * tile + margin;
-                        },
-                        Piece::S => {
-                            draw_rectangle(x + width + tile, y, 2.0 * tile, tile, Piece::S.color());
-                            draw_rectangle(x + width, y + tile, 2.0 * tile, tile, Piece::S.color());
-                            width += 3.0 * tile + margin;
-                        },
-                        Piece::T => {
-                            draw_rectangle(x + width + tile, y, tile, tile, Piece::T.color());
-                            draw_rectangle(x + width, y + tile, 3.0 * tile, tile, Piece::T.color());
-                            width += 3.0 * tile + margin;
-                        },
-                        Piece::Z => {
-                            draw_rectangle(x + width, y, 2.0 * tile, tile, Piece::Z.color());
-                            draw_rectangle(x + width + tile, y + tile, 2.0 * tile, tile, Piece::Z.color());
-                            width += 3.0 * tile + margin;
-                        },
-                    }
-                },
+            if let Placement::Place { .. } = placement {
+                let page = FumenPage::with_operation(board, placement, &summary);
+                board = page.next_board();
+                pages.push(page);
             }
         }
-        2.0 * tile
+        encode(&pages)
     }
 }
 
@@ -163,3 +174,81 @@ impl Hash for Pc {
         self.get_final_board().hash(state);
     }
 }
+
+// Sorts solutions best-first for the objective and drops duplicates of the same PC, keeping the best
+// way to build each one. Solutions that break the PC loop always go last. Attack and T-spins use
+// the best spin each placement could get, so they're the most a solution can send.
+pub fn rank_pcs(pcs: &[Pc], objective: PcObjective, stats: &Stats, attack_table: AttackTable) -> Vec<Pc> {
+    let mut sorted = pcs.to_vec();
+    // Ties go to lower PCs, then to more damage
+    sorted.sort_by_cached_key(|pc| {
+        let attack = pc.attack(stats, attack_table);
+        let score = pc.score(stats, attack_table);
+        let height = pc.height();
+        let key = match objective {
+            PcObjective::LowestHeight => (0, height as i64, -(attack as i64), -(score as i64)),
+            PcObjective::MaxAttack => (-(attack as i64), height as i64, -(score as i64), 0),
+            PcObjective::FewestHolds => (pc.holds() as i64, height as i64, -(attack as i64), -(score as i64)),
+            PcObjective::TSpinFirst => (-(pc.tspins() as i64), -(attack as i64), height as i64, -(score as i64)),
+        };
+        (pc.breaks_loop(), key)
+    });
+    let mut seen: HashSet<Pc> = HashSet::new();
+    sorted.retain(|pc| seen.insert(pc.clone()));
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{detect_spin, Spin};
+    use crate::search::BitBoard;
+    use crate::state::{Rotation, Tile};
+
+    // The bottom two rows of a board, with 'X' for filled cells
+    fn board(rows: [&str; 2]) -> Board {
+        let mut board = Board::new();
+        for (i, row) in rows.iter().enumerate() {
+            for (c, ch) in row.chars().enumerate() {
+                if ch == 'X' {
+                    board.tiles[21 + i][c] = Tile::from(Piece::I);
+                }
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn all_spins_rank_below_tspins() {
+        let tsd = Pc::new(
+            board(["...XXXXXXX", "X.XXXXXXXX"]),
+            vec![Placement::place(Piece::T, 21, 1, Rotation::Flip)],
+            vec![ClearType::new(Piece::T, Spin::Full, 2)],
+            false,
+        );
+        // With all-spins on, an S stuck in its slot counts as a mini
+        let s_board = board(["X..XXXXXXX", "..XXXXXXXX"]);
+        let spin = detect_spin(&BitBoard::from(&s_board), Piece::S, 22, 1, Rotation::Normal, Some((0, 0)), true);
+        let s_spin = Pc::new(
+            s_board,
+            vec![Placement::place(Piece::S, 22, 1, Rotation::Normal)],
+            vec![ClearType::new(Piece::S, spin, 2)],
+            false,
+        );
+        let double = Pc::new(
+            board(["XXXXXXXX..", "XXXXXXXX.."]),
+            vec![Placement::place(Piece::O, 21, 8, Rotation::Normal)],
+            vec![ClearType::new(Piece::O, Spin::None, 2)],
+            false,
+        );
+        let stats = Stats::new();
+        let table = AttackTable::Guideline;
+        assert_eq!(s_spin.attack(&stats, table), double.attack(&stats, table));
+        // The S-spin only beats the plain double on score
+        let best_first = [tsd.clone(), s_spin.clone(), double.clone()];
+        let pcs = [s_spin, double, tsd];
+        for objective in [PcObjective::MaxAttack, PcObjective::TSpinFirst] {
+            assert_eq!(rank_pcs(&pcs, objective, &stats, table), best_first);
+        }
+    }
+}
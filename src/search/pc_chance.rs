@@ -0,0 +1,316 @@
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::thread;
+use crossbeam_channel::Sender;
+use crate::pattern::QueuePattern;
+use crate::state::{Game, Piece::{self, *}, RotationSystem};
+use super::{search_pcs, BitBoard, PcState};
+
+// Result of trying every way the queue could continue
+#[derive(Clone, Debug)]
+pub struct PcChance {
+    pub successes: usize,
+    pub total: usize,
+    pub failing: Vec<Vec<Piece>>, // Whole sequences that can't PC, current piece first
+}
+
+impl PcChance {
+    pub fn percent(&self) -> f32 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        100.0 * self.successes as f32 / self.total as f32
+    }
+}
+
+pub enum PcChanceUpdate {
+    Progress(usize, usize), // Continuations checked so far, out of how many
+    Done(PcChance),
+}
+
+// Everything known about a position that we want the PC chance of
+#[derive(Clone, Debug)]
+pub struct ChanceQuery {
+    pub board: BitBoard,
+    pub hold: Option<Piece>,
+    pub held: bool,
+    pub known: Vec<Piece>, // Current piece followed by the visible queue
+    pub bag_left: Vec<Piece>, // Pieces of the current 7-bag that haven't been seen yet
+    pub height: u8,
+    pub pattern: Option<QueuePattern>, // Queues to try instead of the 7-bag continuations, current piece first
+}
+
+impl ChanceQuery {
+    pub fn from(game: &Game, height: u8) -> Result<Self, &'static str> {
+        let bag_left = game.randomizer.seven_bag_left().ok_or("Only works with a 7-bag randomizer")?;
+        let mut known: Vec<Piece> = game.piece.into_iter().collect();
+        known.extend(game.queue.iter());
+        Self::new(BitBoard::from(&game.board), game.hold, game.held, known, bag_left, height)
+    }
+
+    // Tries every queue the pattern covers from the current board and hold, like sfinder's percent
+    pub fn from_pattern(game: &Game, pattern: QueuePattern, height: u8) -> Result<Self, &'static str> {
+        let mut query = Self::new(BitBoard::from(&game.board), game.hold, game.held, Vec::new(), Vec::new(), height)?;
+        query.pattern = Some(pattern);
+        Ok(query)
+    }
+
+    pub fn new(
+        board: BitBoard,
+        hold: Option<Piece>,
+        held: bool,
+        known: Vec<Piece>,
+        bag_left: Vec<Piece>,
+        height: u8,
+    ) -> Result<Self, &'static str> {
+        let minos = board.mino_count() as u8;
+        if board.rows[..23 - height as usize].iter().any(|&row| row != 0) || minos > 10 * height {
+            return Err("Stack is taller than the PC");
+        }
+        if !(10 * height - minos).is_multiple_of(4) {
+            return Err("Mino count doesn't fit a PC of this height");
+        }
+        Ok(Self {
+            board,
+            hold,
+            held,
+            known,
+            bag_left,
+            height,
+            pattern: None,
+        })
+    }
+
+    // Pieces needed to finish the PC, plus one extra to have a choice with hold
+    fn pieces_needed(&self) -> usize {
+        (10 * self.height as usize - self.board.mino_count() as usize) / 4 + 1
+    }
+
+    // Every sequence of pieces the game could deal from here, long enough to finish the PC
+    pub fn continuations(&self) -> Vec<Vec<Piece>> {
+        if let Some(pattern) = &self.pattern {
+            // Only as much of each queue as the PC can use, which can make some of them the same
+            let length = self.pieces_needed().saturating_sub(self.hold.is_some() as usize);
+            let mut seen: HashSet<Vec<Piece>> = HashSet::new();
+            return pattern.sequences()
+                .map(|sequence| sequence.into_iter().take(length).collect::<Vec<Piece>>())
+                .filter(|sequence| seen.insert(sequence.clone()))
+                .collect();
+        }
+        let have = self.known.len() + self.hold.is_some() as usize;
+        let mut known = self.known.clone();
+        known.truncate(self.pieces_needed().saturating_sub(self.hold.is_some() as usize));
+        let unseen = self.pieces_needed().saturating_sub(have);
+        bag_sequences(&self.bag_left, unseen).into_iter().map(|tail| [known.clone(), tail].concat()).collect()
+    }
+
+    // Returns None if it got cancelled. `progress` gets called after every continuation.
+    pub fn calculate(
+        &self,
+        rotation_system: RotationSystem,
+        cancel_flag: &AtomicBool,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Option<PcChance> {
+        let continuations = self.continuations();
+        let total = continuations.len();
+        let mut chance = PcChance {
+            successes: 0,
+            total,
+            failing: Vec::new(),
+        };
+        // Starts of sequences already known to PC. A PC found for one sequence works for every
+        // other sequence that starts with the pieces it used, and those come right after each other.
+        let mut solved_starts: HashSet<Vec<Piece>> = HashSet::new();
+
+        for (i, sequence) in continuations.into_iter().enumerate() {
+            let known = (0..=sequence.len()).any(|length| solved_starts.contains(&sequence[..length]));
+            let solved = match sequence.split_first() {
+                _ if known => true,
+                Some((&piece, queue)) => {
+                    let state = PcState::new(self.board, Some(piece), self.hold, self.held, self.height);
+                    match first_pc(state, queue, rotation_system, cancel_flag)? {
+                        Some(used) => {
+                            solved_starts.insert(sequence[..used].to_vec());
+                            true
+                        },
+                        None => false,
+                    }
+                },
+                None => self.board.is_empty(),
+            };
+            if solved {
+                chance.successes += 1;
+            } else {
+                chance.failing.push(sequence);
+            }
+            progress(i + 1, total);
+        }
+        Some(chance)
+    }
+}
+
+// Looks for a PC from the state, stopping at the first one. `queue` is everything after the state's
+// current piece. Returns how many pieces that PC needed counting the current one, or None inside if
+// there's no PC. Returns None if cancelled.
+fn first_pc(
+    state: PcState,
+    queue: &[Piece],
+    rotation_system: RotationSystem,
+    cancel_flag: &AtomicBool,
+) -> Option<Option<usize>> {
+    let mut used = None;
+    let finished = search_pcs(vec![state], queue, rotation_system, cancel_flag, |solved, _| {
+        // The solved state's current piece is the first one it didn't need
+        used = Some(solved.queue_used as usize);
+        false
+    });
+    if finished {
+        Some(used)
+    } else {
+        None
+    }
+}
+
+// All orders the next `length` pieces could come in, dealing out of what's left of the current bag
+// first and then fresh bags
+fn bag_sequences(bag_left: &[Piece], length: usize) -> Vec<Vec<Piece>> {
+    if length == 0 {
+        return vec![Vec::new()];
+    }
+    let bag = if bag_left.is_empty() {
+        vec![I, J, L, O, S, T, Z]
+    } else {
+        bag_left.to_vec()
+    };
+    let mut sequences: Vec<Vec<Piece>> = Vec::new();
+    for (i, &piece) in bag.iter().enumerate() {
+        let mut rest = bag.clone();
+        rest.remove(i);
+        for tail in bag_sequences(&rest, length - 1) {
+            let mut sequence = vec![piece];
+            sequence.extend(tail);
+            sequences.push(sequence);
+        }
+    }
+    sequences
+}
+
+pub fn find_pc_chance(
+    query: ChanceQuery,
+    rotation_system: RotationSystem,
+    tx: Sender<PcChanceUpdate>,
+) -> Arc<AtomicBool> {
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let cloned_flag = cancel_flag.clone();
+
+    thread::spawn(move || {
+        let chance = query.calculate(rotation_system, &cloned_flag, |done, total| {
+            // Don't flood the other thread with updates
+            if done.is_multiple_of(10) || done == total {
+                tx.send(PcChanceUpdate::Progress(done, total)).ok();
+            }
+        });
+        if let Some(chance) = chance {
+            tx.send(PcChanceUpdate::Done(chance)).ok();
+        }
+    });
+    cancel_flag
+}
+
+// Works out the chance of each of the positions one after another, sending each result back with the
+// index it came with as soon as it's done
+pub fn find_pc_chances(
+    queries: Vec<(usize, ChanceQuery)>,
+    rotation_system: RotationSystem,
+    tx: Sender<(usize, f32)>,
+) -> Arc<AtomicBool> {
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let cloned_flag = cancel_flag.clone();
+
+    thread::spawn(move || {
+        for (i, query) in queries.into_iter() {
+            match query.calculate(rotation_system, &cloned_flag, |_, _| {}) {
+                Some(chance) => {
+                    if tx.send((i, chance.percent())).is_err() {
+                        return;
+                    }
+                },
+                None => return,
+            }
+        }
+    });
+    cancel_flag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Board, Rotation};
+
+    // Four full rows except for an I-shaped well in the leftmost column
+    fn i_well() -> BitBoard {
+        let mut board = BitBoard::new();
+        for row in board.rows[19..].iter_mut() {
+            *row = 0b11_1111_1110;
+        }
+        board
+    }
+
+    // PCO with the I kept in hold for the second bag
+    fn pco() -> BitBoard {
+        let board = Board::new()
+            .with_placement(J, 22, 1, Rotation::Normal)
+            .with_placement(O, 20, 1, Rotation::Normal)
+            .with_placement(L, 19, 1, Rotation::Flip)
+            .with_placement(Z, 22, 7, Rotation::Normal)
+            .with_placement(T, 21, 9, Rotation::Ccw)
+            .with_placement(S, 20, 8, Rotation::Normal);
+        BitBoard::from(&board)
+    }
+
+    // Chance over every way a fresh bag could start
+    fn fresh_bag_chance(board: BitBoard, hold: Option<Piece>) -> PcChance {
+        let query = ChanceQuery::new(board, hold, false, Vec::new(), Vec::new(), 4).unwrap();
+        query.calculate(RotationSystem::Srs, &AtomicBool::new(false), |_, _| {}).unwrap()
+    }
+
+    #[test]
+    fn needs_an_i_in_the_first_two() {
+        let chance = fresh_bag_chance(i_well(), None);
+        assert_eq!((chance.successes, chance.total), (12, 42));
+        assert!(chance.failing.iter().all(|sequence| !sequence.contains(&I)));
+        // With the I already in hold, it can't fail
+        let chance = fresh_bag_chance(i_well(), Some(I));
+        assert_eq!(chance.successes, chance.total);
+    }
+
+    #[test]
+    fn deals_the_rest_of_the_bag() {
+        let query = ChanceQuery::new(i_well(), None, false, vec![T], vec![I, O], 4).unwrap();
+        assert_eq!(query.continuations(), vec![vec![T, I], vec![T, O]]);
+        let chance = query.calculate(RotationSystem::Srs, &AtomicBool::new(false), |_, _| {}).unwrap();
+        assert_eq!((chance.successes, chance.total), (1, 2));
+        assert_eq!(chance.failing, vec![vec![T, O]]);
+    }
+
+    #[test]
+    fn pco_second_bag() {
+        let chance = fresh_bag_chance(pco(), Some(I));
+        assert_eq!((chance.successes, chance.total), (717, 840));
+    }
+
+    #[test]
+    fn checks_the_stack_fits() {
+        assert!(ChanceQuery::new(pco(), None, false, Vec::new(), Vec::new(), 2).is_err());
+        let mut board = BitBoard::new();
+        board.rows[22] = 0b111;
+        assert!(ChanceQuery::new(board, None, false, Vec::new(), Vec::new(), 4).is_err());
+    }
+
+    #[test]
+    fn cancelling_stops_it() {
+        let query = ChanceQuery::new(pco(), Some(I), false, Vec::new(), Vec::new(), 4).unwrap();
+        assert!(query.calculate(RotationSystem::Srs, &AtomicBool::new(true), |_, _| {}).is_none());
+    }
+}
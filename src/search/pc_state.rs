@@ -1,9 +1,9 @@
-use crate::state::{Board, Game, Piece};
-use super::{get_locations, Placement};
+use crate::state::{Game, Piece, RotationSystem};
+use super::{get_locations, BitBoard, Placement};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct PcState {
-    pub board: Board,
+    pub board: BitBoard,
     pub queue_used: u8, // How many pieces from queue have been used
     pub piece: Option<Piece>,
     pub hold: Option<Piece>,
@@ -12,61 +12,47 @@ pub struct PcState {
 }
 
 impl PcState {
-    pub fn from(game: &Game, height: u8) -> Self {
+    pub fn new(board: BitBoard, piece: Option<Piece>, hold: Option<Piece>, held: bool, height: u8) -> Self {
         Self {
-            board: game.board,
+            board,
             queue_used: 0,
-            piece: game.piece,
-            hold: game.hold,
-            held: game.held,
+            piece,
+            hold,
+            held,
             height,
         }
     }
 
+    pub fn from(game: &Game, height: u8) -> Self {
+        Self::new(BitBoard::from(&game.board), game.piece, game.hold, game.held, height)
+    }
+
     pub fn is_solved(&self) -> bool {
-        for c in 0..10 {
-            for r in 0..(23 - self.height) {
-                if self.board.tiles[r as usize][c].piece.is_some() {
-                    return false;
-                }
-            }
-            for r in (23 - self.height)..23 {
-                if self.board.tiles[r as usize][c].piece.is_none() {
-                    return false;
-                }
-            }
-        }
-        true
+        let top = 23 - self.height as usize;
+        self.board.rows[..top].iter().all(|&row| row == 0)
+            && (top..23).all(|r| self.board.is_row_full(r))
     }
 
     // Returns true if this state will obviously fail to PC -- allows use of backtracking search to
     // cut down search time
-    pub fn fails_early(&self, queue: &Vec<Piece>) -> bool {
-        // Counting number of minos on board
-        let mut mino_count = 0;
-
-        for r in 0..23 {
-            for c in 0..10 {
-                if self.board.tiles[r as usize][c].piece.is_some() {
-                    mino_count += 1;
-                    // If we have a mino above PC height
-                    if r < 23 - self.height {
-                        return true;
-                    }
-                }
-            }
+    pub fn fails_early(&self, queue: &[Piece]) -> bool {
+        let top = 23 - self.height as usize;
+        // If we have a mino above PC height
+        if self.board.rows[..top].iter().any(|&row| row != 0) {
+            return true;
         }
+        // Counting number of minos on board
+        let mino_count = self.board.mino_count() as u8;
         // If we have a number of minos that won't allow PC
         if (10 * self.height - mino_count) % 4 != 0 {
             return true;
         }
 
-        // If number of pieces placed + pieces in queue isn't enough minos
-        let minos_to_place = (queue.len() + 1 + if self.hold.is_some() {
-            1
-        } else {
-            0
-        }) * 4;
+        // If the pieces we have left can't fill up the rest of the PC
+        let pieces_left = queue.len().saturating_sub(self.queue_used as usize)
+            + self.piece.is_some() as usize
+            + self.hold.is_some() as usize;
+        let minos_to_place = pieces_left * 4;
         if minos_to_place as u8 + mino_count < 10 * self.height {
             return true;
         }
@@ -79,13 +65,13 @@ impl PcState {
             let mut walled_off = true;
             let mut open_this_col = 0;
 
-            for r in (23 - self.height)..23 {
-                if self.board.tiles[r as usize][c].piece.is_some() {
+            for &row in self.board.rows[top..].iter() {
+                if row & (1 << c) != 0 {
                     continue;
                 }
                 open_this_col += 1;
                 // If this tile is open and left tile is also open, then it isn't walled off
-                if c > 0 && self.board.tiles[r as usize][c - 1].piece.is_none() {
+                if c > 0 && row & (1 << (c - 1)) == 0 {
                     walled_off = false;
                 }
             }
@@ -108,10 +94,10 @@ impl PcState {
         return false;
     }
 
-    pub fn successors(&self, queue: &Vec<Piece>) -> Vec<(Self, Placement)> {
+    pub fn successors(&self, queue: &[Piece], rotation_system: RotationSystem) -> Vec<(Self, Placement)> {
         let locations;
         if let Some(piece) = self.piece {
-            locations = get_locations(&self.board, piece);
+            locations = get_locations(&self.board, rotation_system, piece);
         } else {
             return Vec::new();
         }
@@ -1,5 +1,5 @@
-use crate::state::{Board, Piece, Rotation};
-use super::Movement;
+use crate::state::{Piece, Rotation, RotationSystem};
+use super::{BitBoard, Movement};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SearchState {
@@ -10,6 +10,9 @@ pub struct SearchState {
 }
 
 impl SearchState {
+    // Every piece's offset map contains its center, so any valid state has its center on the board
+    pub const NUM_INDICES: usize = 4 * 23 * 10;
+
     pub fn new(row: i8, col: i8, rotation: Rotation, piece: Piece) -> Self {
         Self {
             row,
@@ -19,32 +22,32 @@ impl SearchState {
         }
     }
 
-    pub fn intersects(&self, board: &Board) -> bool {
-        for &(offset_row, offset_col) in self.piece.offset_map(self.rotation).iter() {
-            let r = self.row as i8 + offset_row;
-            let c = self.col as i8 + offset_col;
-            if r < 0 || r > 22 || c < 0 || c > 9 || board.tiles[r as usize][c as usize].piece.is_some() {
-                return true;
-            }
-        }
-        false
+    // Unique index of this state's position for a given piece, in 0..NUM_INDICES
+    pub fn index(&self) -> usize {
+        (self.rotation as usize * 23 + self.row as usize) * 10 + self.col as usize
     }
 
-    pub fn successors(&self, board: &Board) -> Vec<(Self, Movement)> {
-        vec![
+    pub fn intersects(&self, board: &BitBoard) -> bool {
+        self.piece.offset_map(self.rotation).iter().any(|&(offset_row, offset_col)| {
+            board.is_filled(self.row + offset_row, self.col + offset_col)
+        })
+    }
+
+    pub fn successors(&self, board: &BitBoard, rotation_system: RotationSystem) -> [(Self, Movement); 8] {
+        [
             (self.left(board), Movement::Left),
             (self.das_left(board), Movement::DasLeft),
             (self.right(board), Movement::Right),
             (self.das_right(board), Movement::DasRight),
             (self.drop(board), Movement::SoftDrop),
-            (self.rotate_cw(board), Movement::RotateCw),
-            (self.rotate_ccw(board), Movement::RotateCcw),
-            (self.rotate_180(board), Movement::Rotate180),
+            (self.rotate(board, rotation_system, self.rotation.cw()), Movement::RotateCw),
+            (self.rotate(board, rotation_system, self.rotation.ccw()), Movement::RotateCcw),
+            (self.rotate(board, rotation_system, self.rotation.flip()), Movement::Rotate180),
         ]
     }
 
-    pub fn left(&self, board: &Board) -> Self {
-        let mut new_state = self.clone();
+    pub fn left(&self, board: &BitBoard) -> Self {
+        let mut new_state = *self;
         new_state.col -= 1;
         if new_state.intersects(board) {
             new_state.col += 1;
@@ -52,8 +55,8 @@ impl SearchState {
         new_state
     }
 
-    pub fn das_left(&self, board: &Board) -> Self {
-        let mut new_state = self.clone();
+    pub fn das_left(&self, board: &BitBoard) -> Self {
+        let mut new_state = *self;
         loop {
             new_state.col -= 1;
             if new_state.intersects(board) {
@@ -64,8 +67,8 @@ impl SearchState {
         new_state
     }
 
-    pub fn right(&self, board: &Board) -> Self {
-        let mut new_state = self.clone();
+    pub fn right(&self, board: &BitBoard) -> Self {
+        let mut new_state = *self;
         new_state.col += 1;
         if new_state.intersects(board) {
             new_state.col -= 1;
@@ -73,8 +76,8 @@ impl SearchState {
         new_state
     }
 
-    pub fn das_right(&self, board: &Board) -> Self {
-        let mut new_state = self.clone();
+    pub fn das_right(&self, board: &BitBoard) -> Self {
+        let mut new_state = *self;
         loop {
             new_state.col += 1;
             if new_state.intersects(board) {
@@ -85,8 +88,8 @@ impl SearchState {
         new_state
     }
 
-    pub fn drop(&self, board: &Board) -> Self {
-        let mut new_state = self.clone();
+    pub fn drop(&self, board: &BitBoard) -> Self {
+        let mut new_state = *self;
         loop {
             new_state.row += 1;
             if new_state.intersects(board) {
@@ -97,110 +100,15 @@ impl SearchState {
         new_state
     }
 
-    pub fn rotate_cw(&self, board: &Board) -> Self {
-        let mut new_state = self.clone();
-        new_state.rotation = match self.rotation {
-            Rotation::Normal => Rotation::Cw,
-            Rotation::Cw => Rotation::Flip,
-            Rotation::Ccw => Rotation::Normal,
-            Rotation::Flip => Rotation::Ccw,
-        };
-        // Handle weird I rotations
-        if new_state.piece == Piece::I {
-            match new_state.rotation {
-                Rotation::Normal => {
-                    new_state.row -= 1;
-                },
-                Rotation::Cw => {
-                    new_state.col += 1;
-                },
-                Rotation::Ccw => {
-                    new_state.col -= 1;
-                },
-                Rotation::Flip => {
-                    new_state.row += 1;
-                },
-            }
-        }
-        new_state.apply_kicks(board, self);
-        new_state
-    }
-
-    pub fn rotate_ccw(&self, board: &Board) -> Self {
-        let mut new_state = self.clone();
-        new_state.rotation = match self.rotation {
-            Rotation::Normal => Rotation::Ccw,
-            Rotation::Cw => Rotation::Normal,
-            Rotation::Ccw => Rotation::Flip,
-            Rotation::Flip => Rotation::Cw,
-        };
-        // Handle weird I rotations
-        if new_state.piece == Piece::I {
-            match new_state.rotation {
-                Rotation::Normal => {
-                    new_state.col -= 1;
-                },
-                Rotation::Cw => {
-                    new_state.row -= 1;
-                },
-                Rotation::Ccw => {
-                    new_state.row += 1;
-                },
-                Rotation::Flip => {
-                    new_state.col += 1;
-                },
-            }
-        }
-        new_state.apply_kicks(board, self);
-        new_state
-    }
-
-    pub fn rotate_180(&self, board: &Board) -> Self {
-        let mut new_state = self.clone();
-        new_state.rotation = match self.rotation {
-            Rotation::Normal => Rotation::Flip,
-            Rotation::Cw => Rotation::Ccw,
-            Rotation::Ccw => Rotation::Cw,
-            Rotation::Flip => Rotation::Normal,
-        };
-        // Handle weird I rotations
-        if new_state.piece == Piece::I {
-            match new_state.rotation {
-                Rotation::Normal => {
-                    new_state.row -= 1;
-                    new_state.col -= 1;
-                },
-                Rotation::Cw => {
-                    new_state.row -= 1;
-                    new_state.col += 1;
-                },
-                Rotation::Ccw => {
-                    new_state.row += 1;
-                    new_state.col -= 1;
-                },
-                Rotation::Flip => {
-                    new_state.row += 1;
-                    new_state.col += 1;
-                },
-            }
-        }
-        new_state.apply_kicks(board, self);
-        new_state
-    }
-
-    fn apply_kicks(&mut self, board: &Board, orig_state: &Self) {
-        let original = self.clone();
-        for &(kick_row, kick_col) in self.piece.kick_map(orig_state.rotation, self.rotation).iter() {
-            self.row = original.row + kick_row;
-            self.col = original.col + kick_col;
-            if self.intersects(board) {
-                continue;
-            }
-            // If we got here, this kick succeeded -- return
-            return;
-        }
+    pub fn rotate(&self, board: &BitBoard, rotation_system: RotationSystem, new_rot: Rotation) -> Self {
+        let kicked = rotation_system.rotate(self.piece, self.row, self.col, self.rotation, new_rot, |row, col| {
+            !Self::new(row, col, new_rot, self.piece).intersects(board)
+        });
         // If none of the kicks worked, leave original state
-        *self = *orig_state;
+        match kicked {
+            Some((row, col, _)) => Self::new(row, col, new_rot, self.piece),
+            None => *self,
+        }
     }
 
     pub fn symmetrical(&self) -> Self {
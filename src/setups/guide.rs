@@ -0,0 +1,60 @@
+use crate::search::{get_locations_with_finesse, BitBoard, Movement, Placement, SearchState};
+use crate::state::{Game, RotationSystem};
+use super::PcSetup;
+
+// What the player should do next to build the pinned setup
+#[derive(Clone, Debug)]
+pub enum GuideStep {
+    Place {
+        placement: Placement, // Where the next piece goes
+        hold_first: bool, // The piece to place comes from hold, so hold before placing it
+        path: Vec<Movement>, // Finesse for the placement
+    },
+    Built,
+    Deviated, // The setup can't be built from here anymore
+}
+
+// Step-by-step help for building one setup, worked out again whenever the position changes
+#[derive(Clone)]
+pub struct SetupGuide {
+    pub setup: PcSetup,
+    pub step: GuideStep,
+}
+
+impl SetupGuide {
+    pub fn new(setup: PcSetup, game: &Game, rotation_system: RotationSystem) -> Self {
+        let mut guide = Self {
+            setup,
+            step: GuideStep::Built,
+        };
+        guide.update(game, rotation_system);
+        guide
+    }
+
+    pub fn update(&mut self, game: &Game, rotation_system: RotationSystem) {
+        if self.setup.is_built(&game.board) {
+            self.step = GuideStep::Built;
+            return;
+        }
+        let mut queue = game.queue.clone();
+        // Same as the setup finder, plan with the piece after the queue if it's already known
+        queue.extend(game.randomizer.known_next());
+        let Some(order) = self.setup.build_order(&game.board, rotation_system, queue, game.piece, game.hold, game.held)
+        else {
+            self.step = GuideStep::Deviated;
+            return;
+        };
+        // Holds never come twice in a row, so a placement is at most one move away
+        let hold_first = order.first() == Some(&Placement::Hold);
+        let placement = order[if hold_first { 1 } else { 0 }];
+        let path = match placement {
+            Placement::Place { piece, row, col, rotation } => {
+                let board = BitBoard::from(&game.board);
+                let locations = get_locations_with_finesse(&board, rotation_system, piece);
+                locations.get(&SearchState::new(row as i8, col as i8, rotation, piece)).cloned().unwrap_or_default()
+            },
+            Placement::Hold => Vec::new(),
+        };
+        self.step = GuideStep::Place { placement, hold_first, path };
+    }
+}
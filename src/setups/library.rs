@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use strum_macros::EnumIter;
+use crate::search::Placement;
+use crate::state::{Board, Piece, Rotation};
+use super::{mirror, PcSetup};
+
+// Setups that ship with the program, in the same format as user setup files
+const BUILTIN_FILES: [(&str, &str); 8] = [
+    ("first_pc.toml", include_str!("../../res/setups/first_pc.toml")),
+    ("second_pc.toml", include_str!("../../res/setups/second_pc.toml")),
+    ("third_pc.toml", include_str!("../../res/setups/third_pc.toml")),
+    ("fourth_pc.toml", include_str!("../../res/setups/fourth_pc.toml")),
+    ("fifth_pc.toml", include_str!("../../res/setups/fifth_pc.toml")),
+    ("sixth_pc.toml", include_str!("../../res/setups/sixth_pc.toml")),
+    ("seventh_pc.toml", include_str!("../../res/setups/seventh_pc.toml")),
+    ("dpc.toml", include_str!("../../res/setups/dpc.toml")),
+];
+
+// Which PC in the 7-PC loop a setup is for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, EnumIter)]
+pub enum PcNumber {
+    #[serde(rename = "1st")]
+    First,
+    #[serde(rename = "2nd")]
+    Second,
+    #[serde(rename = "3rd")]
+    Third,
+    #[serde(rename = "4th")]
+    Fourth,
+    #[serde(rename = "5th")]
+    Fifth,
+    #[serde(rename = "6th")]
+    Sixth,
+    #[serde(rename = "7th")]
+    Seventh,
+    #[serde(rename = "DPC")]
+    Dpc,
+}
+
+impl PcNumber {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PcNumber::First => "1st PC",
+            PcNumber::Second => "2nd PC",
+            PcNumber::Third => "3rd PC",
+            PcNumber::Fourth => "4th PC",
+            PcNumber::Fifth => "5th PC",
+            PcNumber::Sixth => "6th PC",
+            PcNumber::Seventh => "7th PC",
+            PcNumber::Dpc => "DPC",
+        }
+    }
+
+    // Pieces placed in the 7-PC loop before this PC starts -- each PC takes 10 pieces. DPC comes
+    // at the same point in the bag as 3rd PC.
+    pub fn start_piece(&self) -> u32 {
+        match self {
+            PcNumber::First => 0,
+            PcNumber::Second => 10,
+            PcNumber::Third | PcNumber::Dpc => 20,
+            PcNumber::Fourth => 30,
+            PcNumber::Fifth => 40,
+            PcNumber::Sixth => 50,
+            PcNumber::Seventh => 60,
+        }
+    }
+
+    // Which PC starts after the given number of pieces, going by where it falls in the bag
+    pub fn from_start_piece(pieces: u32) -> Self {
+        match pieces % 7 {
+            0 => PcNumber::First,
+            1 => PcNumber::Sixth,
+            2 => PcNumber::Fourth,
+            3 => PcNumber::Second,
+            4 => PcNumber::Seventh,
+            5 => PcNumber::Fifth,
+            _ => PcNumber::Third,
+        }
+    }
+}
+
+// Either one PC or a list of them, for a setup that works the same way in more than one PC
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PcNumbers {
+    One(PcNumber),
+    Many(Vec<PcNumber>),
+}
+
+impl PcNumbers {
+    fn to_vec(&self) -> Vec<PcNumber> {
+        match self {
+            PcNumbers::One(pc) => vec![*pc],
+            PcNumbers::Many(pcs) => pcs.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SetupEntry {
+    name: String,
+    pc: PcNumbers,
+    save: Option<Piece>,
+    #[serde(default)]
+    mirror: bool, // Also add the mirrored setup, named with a trailing '*'
+    #[serde(default)]
+    hold_at_start: Vec<Piece>,
+    #[serde(default)]
+    still_to_come: Vec<Piece>,
+    placements: Vec<PlacementEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PlacementEntry {
+    piece: Piece,
+    row: u8,
+    col: u8,
+    rotation: Rotation,
+}
+
+// Something wrong with a setup file, or with one entry in it
+#[derive(Clone, Debug)]
+pub struct SetupError {
+    pub source: String, // File the problem is in
+    pub entry: Option<(usize, String)>, // Index and name of the entry, if the problem is with one
+    pub message: String,
+}
+
+impl Display for SetupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.entry {
+            Some((index, name)) if name.is_empty() => write!(f, "{} entry {}: {}", self.source, index + 1, self.message),
+            Some((index, name)) => write!(f, "{} entry {} ({}): {}", self.source, index + 1, name, self.message),
+            None => write!(f, "{}: {}", self.source, self.message),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SetupLibrary {
+    setups: HashMap<PcNumber, Vec<PcSetup>>,
+    pub errors: Vec<SetupError>,
+}
+
+impl SetupLibrary {
+    pub fn new() -> Self {
+        Self {
+            setups: HashMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    // The built-in setups, plus everything in the user's setup directory
+    pub fn load() -> Self {
+        let mut library = Self::builtin();
+        if let Some(dir) = Self::user_dir() {
+            library.add_dir(&dir);
+        }
+        library
+    }
+
+    pub fn builtin() -> Self {
+        let mut library = Self::new();
+        for (source, text) in BUILTIN_FILES {
+            library.add_file(source, text);
+        }
+        library
+    }
+
+    // Where users can drop their own .toml or .json setup files
+    pub fn user_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("tetris-tool").join("setups"))
+    }
+
+    pub fn get(&self, pc: PcNumber) -> &[PcSetup] {
+        self.setups.get(&pc).map(|setups| setups.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn len(&self) -> usize {
+        self.setups.values().map(|setups| setups.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Loads every setup file in the directory, in name order. A missing directory is fine.
+    pub fn add_dir(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml" || ext == "json"))
+            .collect();
+        paths.sort();
+        for path in paths.iter() {
+            let source = path.display().to_string();
+            match fs::read_to_string(path) {
+                Ok(text) => self.add_file(&source, &text),
+                Err(err) => self.errors.push(SetupError { source, entry: None, message: err.to_string() }),
+            }
+        }
+    }
+
+    // Parses a whole file, keeping every valid entry even if others in the file are broken
+    pub fn add_file(&mut self, source: &str, text: &str) {
+        let entries = if source.ends_with(".json") {
+            parse_json(text)
+        } else {
+            parse_toml(text)
+        };
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(message) => {
+                self.errors.push(SetupError { source: source.to_string(), entry: None, message });
+                return;
+            },
+        };
+        // Mirrors go after all of the originals from the same file
+        let mut mirrors: Vec<(PcNumber, PcSetup)> = Vec::new();
+        for (index, entry) in entries.into_iter().enumerate() {
+            match entry.and_then(|entry| validate(&entry).map(|setup| (entry, setup))) {
+                Ok((entry, setup)) => {
+                    for pc in entry.pc.to_vec() {
+                        if entry.mirror {
+                            mirrors.push((pc, mirror(&setup)));
+                        }
+                        self.setups.entry(pc).or_default().push(setup.clone());
+                    }
+                },
+                Err((name, message)) => self.errors.push(SetupError {
+                    source: source.to_string(),
+                    entry: Some((index, name)),
+                    message,
+                }),
+            }
+        }
+        for (pc, setup) in mirrors {
+            self.setups.entry(pc).or_default().push(setup);
+        }
+    }
+}
+
+// Each entry is deserialized on its own so that one bad entry doesn't throw out the whole file
+type EntryResult = Result<SetupEntry, (String, String)>;
+
+fn entry_name(name: Option<&str>) -> String {
+    name.unwrap_or_default().to_string()
+}
+
+fn parse_toml(text: &str) -> Result<Vec<EntryResult>, String> {
+    let table: toml::Table = toml::from_str(text).map_err(|err| err.message().to_string())?;
+    let Some(toml::Value::Array(entries)) = table.get("setups") else {
+        return Err(String::from("expected a [[setups]] array"));
+    };
+    Ok(entries.iter().map(|value| {
+        let name = entry_name(value.get("name").and_then(|name| name.as_str()));
+        value.clone().try_into::<SetupEntry>().map_err(|err| (name, err.message().to_string()))
+    }).collect())
+}
+
+fn parse_json(text: &str) -> Result<Vec<EntryResult>, String> {
+    let value: serde_json::Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+    let Some(serde_json::Value::Array(entries)) = value.get("setups") else {
+        return Err(String::from("expected a \"setups\" array"));
+    };
+    Ok(entries.iter().map(|value| {
+        let name = entry_name(value.get("name").and_then(|name| name.as_str()));
+        serde_json::from_value::<SetupEntry>(value.clone()).map_err(|err| (name, err.to_string()))
+    }).collect())
+}
+
+// Checks that the pieces fit on the board without overlapping each other
+fn validate(entry: &SetupEntry) -> Result<PcSetup, (String, String)> {
+    let fail = |message: String| Err((entry.name.clone(), message));
+    if entry.name.trim().is_empty() {
+        return fail(String::from("name is empty"));
+    }
+    if entry.placements.is_empty() {
+        return fail(String::from("no placements"));
+    }
+    if entry.pc.to_vec().is_empty() {
+        return fail(String::from("not listed under any PC"));
+    }
+    let mut board = Board::new();
+    let mut placements = Vec::new();
+    for (i, p) in entry.placements.iter().enumerate() {
+        for &(offset_row, offset_col) in p.piece.offset_map(p.rotation).iter() {
+            let row = p.row as i8 + offset_row;
+            let col = p.col as i8 + offset_col;
+            if !(3..23).contains(&row) || !(0..10).contains(&col) {
+                return fail(format!("placement {} ({:?}) goes off the board", i + 1, p.piece));
+            }
+            if board.tiles[row as usize][col as usize].is_filled() {
+                return fail(format!("placement {} ({:?}) overlaps another piece", i + 1, p.piece));
+            }
+        }
+        board = board.with_placement(p.piece, p.row, p.col, p.rotation);
+        placements.push(Placement::place(p.piece, p.row, p.col, p.rotation));
+    }
+    let setup = match entry.save {
+        Some(save) => PcSetup::new_with_save(&entry.name, placements, save),
+        None => PcSetup::new(&entry.name, placements),
+    };
+    Ok(setup.with_prerequisites(entry.hold_at_start.clone(), entry.still_to_come.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::setup_pcs;
+
+    #[test]
+    fn builtin_tables_load() {
+        let library = SetupLibrary::builtin();
+        let errors: Vec<String> = library.errors.iter().map(|e| e.to_string()).collect();
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn every_pc_has_setups_within_its_piece_limit() {
+        let library = SetupLibrary::builtin();
+        for pc_piece_num in (0..70).step_by(10) {
+            for (limit, pc) in setup_pcs(pc_piece_num) {
+                let setups = library.get(pc);
+                assert!(!setups.is_empty(), "no setups for {pc:?}");
+                for setup in setups {
+                    assert!(setup.placements.len() <= limit, "{} places too many pieces for {pc:?}", setup.name);
+                }
+            }
+        }
+    }
+}
@@ -1,19 +1,11 @@
 mod pc_setup;
 mod setup_state;
 mod helpers;
-mod first_pc;
-mod second_pc;
-mod third_pc;
-mod fourth_pc;
-mod fifth_pc;
-mod dpc;
+mod library;
+mod guide;
 
 pub use pc_setup::*;
 pub use setup_state::*;
 pub use helpers::*;
-pub use first_pc::*;
-pub use second_pc::*;
-pub use third_pc::*;
-pub use fourth_pc::*;
-pub use fifth_pc::*;
-pub use dpc::*;
+pub use library::*;
+pub use guide::*;
@@ -1,11 +1,11 @@
-use macroquad::prelude::*;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::{Hash, Hasher};
-use crate::search::Placement;
-use crate::state::Board;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::fumen::{encode, FumenPage};
+use crate::pattern::QueuePattern;
+use crate::search::{BitBoard, ChanceQuery, Placement};
+use crate::state::{Board, Game, RotationSystem, Tile};
 use crate::state::Piece::{self, *};
-use crate::util::font::text_small;
-use crate::util::window::*;
 use super::SetupState;
 
 #[derive(Clone, PartialOrd, Ord)]
@@ -13,6 +13,8 @@ pub struct PcSetup {
     pub name: String,
     pub placements: Vec<Placement>,
     pub target_save: Option<Piece>, // The piece that we want to have on hold at the end
+    pub hold_at_start: Vec<Piece>, // One of these has to have been on hold when the PC started, if any
+    pub still_to_come: Vec<Piece>, // Pieces needed after the setup is built, e.g. a second T
 }
 
 impl PcSetup {
@@ -21,15 +23,60 @@ impl PcSetup {
             name: name.to_string(),
             placements,
             target_save: None,
+            hold_at_start: Vec::new(),
+            still_to_come: Vec::new(),
         }
     }
 
     pub fn new_with_save(name: &str, placements: Vec<Placement>, target_save: Piece) -> Self {
         Self {
-            name: name.to_string(),
-            placements,
             target_save: Some(target_save),
+            ..Self::new(name, placements)
+        }
+    }
+
+    pub fn with_prerequisites(mut self, hold_at_start: Vec<Piece>, still_to_come: Vec<Piece>) -> Self {
+        self.hold_at_start = hold_at_start;
+        self.still_to_come = still_to_come;
+        self
+    }
+
+    // Checks what the board and queue alone can't show -- a setup can be buildable but pointless,
+    // e.g. an extra-T setup when there's no second T coming
+    pub fn meets_prerequisites(&self, game: &Game) -> bool {
+        if !self.hold_at_start.is_empty()
+                && !game.pc_start_hold.is_some_and(|hold| self.hold_at_start.contains(&hold)) {
+            return false;
+        }
+        if self.still_to_come.is_empty() {
+            return true;
         }
+        // Without a plain 7-bag there's no telling what's still to come
+        let Some(bag_left) = game.randomizer.seven_bag_left() else {
+            return true;
+        };
+        let mut pool: Vec<Piece> = game.piece.into_iter()
+            .chain(game.hold)
+            .chain(game.queue.iter().copied())
+            .chain(bag_left)
+            .collect();
+        // The setup's own pieces come out of the pool first
+        let (remaining_placements, _) = self.get_remaining_placements(&game.board);
+        let needed = remaining_placements.iter()
+            .filter_map(|placement| match placement {
+                Placement::Place { piece, .. } => Some(*piece),
+                Placement::Hold => None,
+            })
+            .chain(self.still_to_come.iter().copied());
+        for piece in needed {
+            match pool.iter().position(|&p| p == piece) {
+                Some(index) => {
+                    pool.swap_remove(index);
+                },
+                None => return false,
+            }
+        }
+        true
     }
 
     // Returns list of remaining placements and the total number of minos filled.
@@ -43,7 +90,7 @@ impl PcSetup {
                 for &(offset_row, offset_col) in piece.offset_map(rotation).iter() {
                     let r = (row as i8 + offset_row) as usize;
                     let c = (col as i8 + offset_col) as usize;
-                    if board.tiles[r][c].piece.is_some() {
+                    if board.tiles[r][c].is_filled() {
                         piece_minos_filled += 1;
                     }
                 }
@@ -62,33 +109,83 @@ impl PcSetup {
     pub fn can_build(
         &self,
         board: &Board,
+        rotation_system: RotationSystem,
         queue: VecDeque<Piece>,
         piece: Option<Piece>,
         hold: Option<Piece>,
         held: bool,
     ) -> bool {
+        self.build_order(board, rotation_system, queue, piece, hold, held).is_some()
+    }
+
+    // Counts how many of the pattern's queues (current piece first, nothing on hold) can build this
+    // setup from the board, out of how many. Only the first `piece_limit` pieces of each queue get
+    // used. Returns None if it got cancelled.
+    pub fn coverage(
+        &self,
+        board: &Board,
+        rotation_system: RotationSystem,
+        pattern: &QueuePattern,
+        piece_limit: usize,
+        cancel_flag: &AtomicBool,
+    ) -> Option<(usize, usize)> {
+        // Cutting the queues short can make some of them the same
+        let mut seen: HashSet<VecDeque<Piece>> = HashSet::new();
+        let mut built = 0;
+        for mut queue in pattern.sequences() {
+            queue.truncate(piece_limit);
+            if !seen.insert(queue.clone()) {
+                continue;
+            }
+            if cancel_flag.load(Ordering::Relaxed) {
+                return None;
+            }
+            let piece = queue.pop_front();
+            if self.can_build(board, rotation_system, queue, piece, None, false) {
+                built += 1;
+            }
+        }
+        Some((built, seen.len()))
+    }
+
+    // True once every piece of the setup is on the board
+    pub fn is_built(&self, board: &Board) -> bool {
+        self.get_remaining_placements(board).0.is_empty()
+    }
+
+    // Returns the moves (holds included) that build the rest of this setup from the given
+    // position, or None if it can't be built
+    pub fn build_order(
+        &self,
+        board: &Board,
+        rotation_system: RotationSystem,
+        queue: VecDeque<Piece>,
+        piece: Option<Piece>,
+        hold: Option<Piece>,
+        held: bool,
+    ) -> Option<Vec<Placement>> {
         // First, eliminate pieces that are already filled by the board
         let (remaining_placements, minos_filled) = self.get_remaining_placements(board);
         // If we have already built the whole thing, don't want to include this in setup list
         if remaining_placements.len() == 0 {
-            return false;
+            return None;
         }
         // If any pieces were partially filled in the setup, can't build
         if (self.placements.len() - remaining_placements.len()) * 4 != minos_filled as usize {
-            return false;
+            return None;
         }
         // Check if we filled in all of the minos on the board -- if not, the board has junk that
         // will prevent the setup from being buildable
         let mut board_mino_count = 0;
         for r in 3..23 {
             for c in 0..10 {
-                if board.tiles[r][c].piece.is_some() {
+                if board.tiles[r][c].is_filled() {
                     board_mino_count += 1;
                 }
             }
         }
         if board_mino_count != minos_filled {
-            return false;
+            return None;
         }
 
         // First, check if our pieces even match the pieces used by the setup
@@ -119,7 +216,7 @@ impl PcSetup {
         // If there is any piece for which the setup needs more than the queue has, can't build
         for &p in &[I, J, L, O, S, T, Z] {
             if setup_counts.get(&p).unwrap_or(&0) > queue_counts.get(&p).unwrap_or(&0) {
-                return false;
+                return None;
             }
         }
 
@@ -130,98 +227,102 @@ impl PcSetup {
 
         // This stack contains all of the remaining placements that need to be made as well as the
         // current state of the queue, piece, and hold piece.
-        let mut stack: Vec<SetupState> = vec![
-            SetupState::new(*board, remaining_placements.clone(), queue.clone(), piece, hold, held),
+        // Each state also carries the moves made to reach it.
+        let mut stack: Vec<(SetupState, Vec<Placement>)> = vec![
+            (SetupState::new(BitBoard::from(board), remaining_placements.clone(), queue.clone(), piece, hold, held),
+                    Vec::new()),
         ];
         let mut visited: HashSet<SetupState> = HashSet::new();
 
-        while let Some(state) = stack.pop() {
+        while let Some((state, moves)) = stack.pop() {
             // Successfully built
             if state.placements.len() == 0 {
-                // Only return true if we saved the correct piece
+                // Only counts if we saved the correct piece
                 if let Some(save) = self.target_save {
                     if state.hold == Some(save) {
-                        return true;
+                        return Some(moves);
                     }
                     continue;
                 }
-                return true;
+                return Some(moves);
             }
             if visited.contains(&state) {
                 continue;
             }
             visited.insert(state.clone());
-            // Iterate over successors and add ones that haven't been visited
-            for successor in state.successors().iter() {
-                if visited.contains(successor) {
+            // Iterate over successors and add ones that haven't been visited -- in reverse, so that
+            // placing the current piece is tried before holding it
+            for (successor, placement) in state.successors(rotation_system).into_iter().rev() {
+                if visited.contains(&successor) {
                     continue;
                 }
-                stack.push(successor.clone());
+                let mut successor_moves = moves.clone();
+                successor_moves.push(placement);
+                stack.push((successor, successor_moves));
             }
         }
         // If we weren't able to find a build solution in the DFS, can't build
-        false
+        None
     }
 
-    fn get_final_board(&self, board: &Board) -> Board {
+    // Works out what the position will look like once this setup is built from the game, so that
+    // the PC chance from there can be calculated. Assumes that the pieces the setup doesn't use
+    // stay in the same order, and that only the target save (if any) ends up on hold.
+    pub fn chance_query(&self, game: &Game) -> Option<ChanceQuery> {
+        let mut bag_left = game.randomizer.seven_bag_left()?;
+        let (remaining_placements, _) = self.get_remaining_placements(&game.board);
+        let mut needed: Vec<Piece> = remaining_placements.iter().filter_map(|&placement| match placement {
+            Placement::Place { piece, .. } => Some(piece),
+            Placement::Hold => None,
+        }).collect();
+        needed.extend(self.target_save);
+
+        // Take out every piece the setup uses, leaving the rest in order. Setups can also use the
+        // piece after the queue when the bag gives it away, so that comes out of the bag instead.
+        let mut left_over: Vec<Piece> = game.hold.into_iter().chain(game.piece).chain(game.queue.iter().copied()).collect();
+        for piece in needed.iter() {
+            if let Some(index) = left_over.iter().position(|p| p == piece) {
+                left_over.remove(index);
+            } else {
+                let index = bag_left.iter().position(|p| p == piece)?;
+                bag_left.remove(index);
+            }
+        }
+        let board = BitBoard::from(&self.get_final_board(&game.board));
+        // Lowest PC height that the setup fits in
+        (1..=4).find_map(|height| {
+            ChanceQuery::new(board, self.target_save, false, left_over.clone(), bag_left.clone(), height).ok()
+        })
+    }
+
+    pub fn get_final_board(&self, board: &Board) -> Board {
         let mut final_board = *board;
         for &placement in self.placements.iter() {
             if let Placement::Place{ piece, row, col, rotation } = placement {
                 for &(offset_row, offset_col) in piece.offset_map(rotation).iter() {
                     let r = (row as i8 + offset_row) as usize;
                     let c = (col as i8 + offset_col) as usize;
-                    final_board.tiles[r][c].piece = Some(piece);
+                    final_board.tiles[r][c] = Tile::from(piece);
                 }
             }
         }
         final_board
     }
 
-    // Returns the height of the board diagram drawn
-    pub fn draw(&self, board: &Board, x: f32, y: f32, scale: f32, font: Font) -> f32 {
-        // Draw name
-        let mut height = text_size_small() + margin();
-        draw_text_ex(&self.name, x + margin(), y + height, text_small(font, WHITE));
-        height += margin();
-
-        // First, construct final board position
-        let final_board = self.get_final_board(board);
-        // Then, find the highest row in the board from which to start drawing
-        let mut start_row = 0;
-        'row: for r in 0..23 {
-            for c in 0..10 {
-                if final_board.tiles[r][c].piece.is_some() {
-                    start_row = r;
-                    break 'row;
-                }
-            }
+    // One fumen page per piece still left to place on the given board
+    pub fn to_fumen(&self, board: &Board) -> String {
+        let (remaining_placements, _) = self.get_remaining_placements(board);
+        let mut pages: Vec<FumenPage> = Vec::new();
+        let mut board = *board;
+        for &placement in remaining_placements.iter() {
+            let page = FumenPage::with_operation(board, placement, &self.name);
+            board = page.next_board();
+            pages.push(page);
         }
-        // Finally, do the board drawing
-        for r in start_row..23 {
-            for c in 0..10 {
-                if let Some(piece) = final_board.tiles[r][c].piece {
-                    // If the mino was already there in the original board, draw in gray
-                    if let Some(_) = board.tiles[r][c].piece {
-                        draw_rectangle(
-                            x + tile_size() * scale * c as f32,
-                            y + height + tile_size() * scale * (r - start_row) as f32,
-                            tile_size() * scale,
-                            tile_size() * scale,
-                            GRAY,
-                        );
-                    } else {
-                        draw_rectangle(
-                            x + tile_size() * scale * c as f32,
-                            y + height + tile_size() * scale * (r - start_row) as f32,
-                            tile_size() * scale,
-                            tile_size() * scale,
-                            piece.color(),
-                        );
-                    }
-                }
-            }
+        if pages.is_empty() {
+            pages.push(FumenPage::new(board));
         }
-        (23 - start_row) as f32 * scale * tile_size() + height
+        encode(&pages)
     }
 }
 
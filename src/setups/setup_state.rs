@@ -1,10 +1,10 @@
 use std::collections::VecDeque;
-use crate::search::{get_locations, Placement, SearchState};
-use crate::state::{Board, Piece};
+use crate::search::{get_locations, BitBoard, Placement, SearchState};
+use crate::state::{Piece, RotationSystem};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct SetupState {
-    pub board: Board,
+    pub board: BitBoard,
     pub placements: Vec<Placement>,
     pub queue: VecDeque<Piece>,
     pub piece: Option<Piece>,
@@ -14,7 +14,7 @@ pub struct SetupState {
 
 impl SetupState {
     pub fn new(
-        board: Board,
+        board: BitBoard,
         placements: Vec<Placement>,
         queue: VecDeque<Piece>,
         piece: Option<Piece>,
@@ -31,8 +31,9 @@ impl SetupState {
         }
     }
 
-    pub fn successors(&self) -> Vec<Self> {
-        let mut list: Vec<Self> = Vec::new();
+    // Returns each state reachable with one more move, along with that move
+    pub fn successors(&self, rotation_system: RotationSystem) -> Vec<(Self, Placement)> {
+        let mut list: Vec<(Self, Placement)> = Vec::new();
         let mut successor;
         // Placing next piece options
         for i in 0..self.placements.len() {
@@ -40,18 +41,19 @@ impl SetupState {
                 if self.piece == Some(piece) {
                     // Check if it is possible to place the piece here
                     let state = SearchState::new(row as i8, col as i8, rotation, piece);
-                    let locations = get_locations(&self.board, piece);
+                    let locations = get_locations(&self.board, rotation_system, piece);
                     if !locations.contains(&state) && !locations.contains(&state.symmetrical()) {
                         continue;
                     }
                     // If it is possible, add this successor
                     successor = self.clone();
-                    if let Placement::Place { piece, row, col, rotation } = successor.placements.swap_remove(i) {
+                    let placement = successor.placements.swap_remove(i);
+                    if let Placement::Place { piece, row, col, rotation } = placement {
                         successor.board = successor.board.with_placement(piece, row, col, rotation);
                     }
                     successor.piece = successor.queue.pop_front();
                     successor.held = false;
-                    list.push(successor);
+                    list.push((successor, placement));
                 }
             }
         }
@@ -65,7 +67,8 @@ impl SetupState {
                 successor.hold = self.piece;
                 successor.piece = successor.queue.pop_front();
             }
-            list.push(successor);
+            successor.held = true;
+            list.push((successor, Placement::Hold));
         }
         list
     }
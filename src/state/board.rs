@@ -1,6 +1,4 @@
 use std::fmt::Debug;
-use macroquad::prelude::*;
-use crate::util::window::*;
 use crate::state::Tile;
 use super::{Piece, Rotation};
 
@@ -16,57 +14,10 @@ impl Board {
         }
     }
 
-    pub fn draw(&self, x: f32, y: f32) {
-        for r in 0..23 {
-            for c in 0..10 {
-                draw_rectangle(
-                    x + c as f32 * tile_size() + grid_thickness() / 2.0,
-                    y + r as f32 * tile_size() + grid_thickness() / 2.0,
-                    tile_size() - grid_thickness(),
-                    tile_size() - grid_thickness(),
-                    self.tiles[r][c].color(),
-                );
-            }
-        }
-    }
-
-    pub fn draw_grid(&self, x: f32, y: f32) {
-        // Horizontal grid lines
-        for r in 3..=23 {
-            draw_line(
-                x,
-                y + r as f32 * tile_size(),
-                x + board_width(),
-                y + r as f32 * tile_size(),
-                grid_thickness(),
-                if r == 3 || r == 23 {
-                    WHITE
-                } else {
-                    Color::new(0.2, 0.2, 0.2, 1.0)
-                },
-            );
-        }
-        // Vertical grid lines
-        for c in 0..=10 {
-            draw_line(
-                x + c as f32 * tile_size(),
-                y + 3.0 * tile_size() + grid_thickness() / 2.0,
-                x + c as f32 * tile_size(),
-                y + board_height() - grid_thickness() / 2.0,
-                grid_thickness(),
-                if c == 0 || c == 10 {
-                    WHITE
-                } else {
-                    Color::new(0.2, 0.2, 0.2, 1.0)
-                },
-            );
-        }
-    }
-
     pub fn is_empty(&self) -> bool {
         for r in 3..23 {
             for c in 0..10 {
-                if self.tiles[r][c].piece.is_some() {
+                if self.tiles[r][c].is_filled() {
                     return false;
                 }
             }
@@ -90,7 +41,7 @@ impl Board {
         let mut cleared = [false; 23];
         'row: for r in 0..23 {
             for c in 0..10 {
-                if self.tiles[r][c].piece.is_none() {
+                if !self.tiles[r][c].is_filled() {
                     continue 'row;
                 }
             }
@@ -111,7 +62,7 @@ impl Board {
         // Finally, make sure to erase the top lines that didn't get overwritten by shift
         for r in 0..offset {
             for c in 0..10 {
-                self.tiles[r + 3][c].piece = None;
+                self.tiles[r + 3][c] = Tile::new();
             }
         }
         return offset as u8;
@@ -124,6 +75,7 @@ impl Debug for Board {
             for c in 0..10 {
                 match self.tiles[r][c].piece {
                     Some(piece) => write!(f, "{:?}", piece)?,
+                    None if self.tiles[r][c].garbage => write!(f, "X")?,
                     None => write!(f, "-")?,
                 };
             }
@@ -1,16 +1,23 @@
-use crossbeam_channel::{Receiver, Sender};
-use macroquad::prelude::*;
-use std::collections::{HashSet, VecDeque};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
-use crate::search::{find_pcs, find_setups, get_finesse_faults, Movement, Pc};
+use rand::rngs::StdRng;
+use std::collections::VecDeque;
+use crate::search::{get_finesse_faults, BitBoard, Movement};
 use crate::state::{Piece, Rotation};
 use crate::logic::*;
-use crate::util::font::*;
-use crate::util::window::*;
+use crate::rng::{random_seed, seeded_rng, Randomizer, RandomizerKind};
 use super::{Board, Tile};
 
+// What undo goes back to -- the position and stats from just before a piece was placed or held
+#[derive(Clone)]
+pub struct UndoEntry {
+    pub board: Board,
+    pub piece: Option<Piece>,
+    pub hold: Option<Piece>,
+    pub held: bool,
+    pub stats: Stats,
+    pub pc_piece_num: u32,
+    pub pc_start_hold: Option<Piece>,
+}
+
 #[derive(Clone)]
 pub struct Game {
     pub board: Board,
@@ -20,26 +27,47 @@ pub struct Game {
     pub hold: Option<Piece>,
     pub queue: VecDeque<Piece>,
     pub rotation: Rotation,
-    pub bag: VecDeque<Piece>,
-    pub last_time: Instant, // Timestamp of when last gravity falling unit occurred
-    pub left_time: Instant, // Timestamp of when last left DAS unit occurred
-    pub right_time: Instant, // Timestamp of when last right DAS unit occurred
-    pub soft_drop_time: Instant, // Timestamp of when last softdrop unit occurred
+    pub last_kick: Option<(i8, i8)>, // Kick offset used by the last move, if it was a rotation
+    pub randomizer: Box<dyn Randomizer>, // Decides which pieces go into the queue
+    pub seed: u64, // Seed that the piece sequence is generated from
+    pub rng: StdRng,
+    pub last_time: u64, // Timestamp (ms) of when last gravity falling unit occurred
+    pub lock_time: u64, // Timestamp (ms) of when the lock delay last started over
+    pub lowest_row: i8, // Lowest row the current piece has fallen to
+    pub lock_resets: u32, // Lock delay restarts used up by moves and rotations since reaching the lowest row
+    pub left_time: u64, // Timestamp (ms) of when last left DAS unit occurred
+    pub right_time: u64, // Timestamp (ms) of when last right DAS unit occurred
+    pub soft_drop_time: u64, // Timestamp (ms) of when last softdrop unit occurred
+    pub left_down: bool, // True while the left key is held
+    pub right_down: bool, // True while the right key is held
+    pub soft_drop_down: bool, // True while the soft drop key is held
     pub left_das_activated: bool, // Becomes true when left key is held long enough for DAS
     pub right_das_activated: bool, // Becomes true when right key is held long enough for DAS
     pub left_priority: bool, // True when left is the most recently held key
-    pub undo_stack: Vec<(Board, Option<Piece>, Option<Piece>, bool, Stats, u32)>, // Board, piece, hold, held, stats
+    pub das_cut_time: u64, // Timestamp (ms) until which DCD holds back auto-shift
+    pub undo_stack: Vec<UndoEntry>,
     pub prev_stats: Stats,
     pub finesse_path: Option<Vec<Movement>>,
     pub my_path: Vec<Movement>,
     pub prev_path: Vec<Movement>,
-    pub pcs: Vec<Pc>,
     pub held: bool,
     pub pc_piece_num: u32,
+    pub pc_start_hold: Option<Piece>, // What was on hold when the current PC started
+    pub events: Vec<GameEvent>, // Events produced since the last step
+    pub game_over: bool, // Set on topping out, until the game is reset or the placement undone
 }
 
 impl Game {
     pub fn new() -> Self {
+        Self::with_seed(random_seed())
+    }
+
+    // Creates a 7-bag game whose piece sequence is fully determined by the seed
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_randomizer(seed, RandomizerKind::SevenBag.create())
+    }
+
+    pub fn with_randomizer(seed: u64, randomizer: Box<dyn Randomizer>) -> Self {
         let mut game = Self {
             board: Board::new(),
             piece: None,
@@ -48,258 +76,125 @@ impl Game {
             hold: None,
             queue: VecDeque::new(),
             rotation: Rotation::Normal,
-            bag: VecDeque::new(),
-            last_time: Instant::now(),
-            left_time: Instant::now(),
-            right_time: Instant::now(),
-            soft_drop_time: Instant::now(),
+            last_kick: None,
+            randomizer,
+            seed,
+            rng: seeded_rng(seed),
+            last_time: 0,
+            lock_time: 0,
+            lowest_row: 0,
+            lock_resets: 0,
+            left_time: 0,
+            right_time: 0,
+            soft_drop_time: 0,
+            left_down: false,
+            right_down: false,
+            soft_drop_down: false,
             left_das_activated: false,
             right_das_activated: false,
             left_priority: false,
+            das_cut_time: 0,
             undo_stack: Vec::new(),
             prev_stats: Stats::new(),
             finesse_path: None,
             my_path: Vec::new(),
             prev_path: Vec::new(),
-            pcs: Vec::new(),
             held: false,
             pc_piece_num: 0,
+            pc_start_hold: None,
+            events: Vec::new(),
+            game_over: false,
         };
         init_queue(&mut game);
         game
     }
 
-    pub fn draw(&mut self, font: Font, stats: &Stats) {
-        self.board.draw(board_x(), board_y());
-        self.draw_piece(board_x(), board_y());
-        self.draw_shadow(board_x(), board_y());
-        self.draw_queue(queue_x(), queue_y(), 0.75, font);
-        self.draw_hold(hold_x(), hold_y(), 0.75, font);
-        self.draw_stats(stats_x(), stats_y(), font, stats);
-        self.draw_piece_num(piece_num_x(), piece_num_y(), font, stats);
-        self.board.draw_grid(board_x(), board_y());
-        self.draw_finesse_path(finesse_x(), finesse_y(), font);
-        self.draw_pcs(pc_x(), pc_y(), 0.5, font, stats);
-        self.draw_strategy(strategy_x(), strategy_y(), font);
-        self.draw_setups(setup_x(), setup_y(), 0.5, font, stats);
-        Game::draw_borders();
-    }
-
-    fn draw_borders() {
-        fn draw_outline(x: f32, y: f32, w: f32, h: f32, thickness: f32, color: Color) {
-            let x1 = x - thickness / 2.0;
-            let x2 = x1 + w;
-            let y1 = y - thickness / 2.0;
-            let y2 = y1 + h;
-            draw_rectangle(x1, y1, w + thickness, thickness, color);
-            draw_rectangle(x1, y1, thickness, h + thickness, color);
-            draw_rectangle(x1, y2, w + thickness, thickness, color);
-            draw_rectangle(x2, y1, thickness, h + thickness, color);
-        }
-        // Board outline
-        draw_outline(board_x(), board_y(), board_width(), board_height(), grid_thickness(), WHITE);
-        // Hold outline
-        draw_outline(hold_x(), hold_y(), hold_width(), hold_height(), grid_thickness(), WHITE);
-        // Finesse outline
-        draw_outline(stats_x(), stats_y(), stats_width(), stats_height(), grid_thickness(), WHITE);
-        // Queue outline
-        draw_outline(queue_x(), queue_y(), queue_width(), queue_height(), grid_thickness(), WHITE);
-        // Piece num outline
-        draw_outline(piece_num_x(), piece_num_y(), piece_num_width(), piece_num_height(), grid_thickness(), WHITE);
-        // Finesse error text outline
-        draw_outline(finesse_x(), finesse_y(), finesse_width(), finesse_height(), grid_thickness(), WHITE);
-        // PC solver outline
-        draw_outline(pc_x(), pc_y(), pc_width(), pc_height(), grid_thickness(), WHITE);
-        // PC strategy outline
-        draw_outline(strategy_x(), strategy_y(), strategy_width(), strategy_height(), grid_thickness(), WHITE);
-        // Setups outline
-        draw_outline(setup_x(), setup_y(), setup_width(), setup_height(), grid_thickness(), WHITE);
-    }
-
-    fn draw_queue(&self, x: f32, y: f32, scale: f32, font: Font) {
-        draw_text_ex("QUEUE", x + margin(), y + tile_size(), text_large(font, WHITE));
-        let mut height: f32 = text_size_large() + 2.0 * margin();
-        // Draw only the first 5 pieces in queue in case we undid moves
-        for &piece in self.queue.iter().take(5) {
-            let (_, h) = piece.draw(x + margin(), y + height, scale);
-            height += h + queue_gap();
-        }
-    }
-
-    fn draw_piece_num(&self, x: f32, y: f32, font: Font, stats: &Stats) {
-        let bag = stats.pieces / 7 + 1;
-        let piece = stats.pieces % 7 + 1;
-        draw_text_ex(&format!("Bag: {}", bag), x + margin(),
-                y + tile_size(), text_normal(font, WHITE));
-        draw_text_ex(&format!("Piece: {}/7", piece), x + margin(),
-                y + tile_size() + text_size_normal(), text_normal(font, WHITE));
-    }
-
-    fn draw_hold(&self, x: f32, y: f32, scale: f32, font: Font) {
-        draw_text_ex("HOLD", x + margin(), y + tile_size(), text_large(font, WHITE));
-        if let Some(hold) = self.hold {
-            hold.draw(x + margin(), y + text_size_large() + 2.0 * margin(), scale);
-        }
+    pub fn refresh_last_time(&mut self, now: u64) {
+        self.last_time = now;
     }
 
-    fn draw_piece(&self, x: f32, y: f32) {
-        if let Some(piece) = self.piece {
-            for &(offset_row, offset_col) in piece.offset_map(self.rotation).iter() {
-                draw_rectangle(
-                    x + (self.piece_col + offset_col) as f32 * tile_size() + grid_thickness() / 2.0,
-                    y + (self.piece_row + offset_row) as f32 * tile_size() + grid_thickness() / 2.0,
-                    tile_size() - grid_thickness(),
-                    tile_size() - grid_thickness(),
-                    piece.color(),
-                );
-            }
+    // Called whenever the piece falls. Reaching a new lowest row starts the lock delay over, and
+    // gives back any move resets that were used up.
+    pub fn piece_fell(&mut self, now: u64) {
+        self.last_kick = None;
+        if self.piece_row > self.lowest_row {
+            self.lowest_row = self.piece_row;
+            self.lock_time = now;
+            self.lock_resets = 0;
         }
     }
 
-    fn draw_shadow(&mut self, x: f32, y: f32) {
-        if let Some(piece) = self.piece {
-            let old_piece_row = self.piece_row;
-            loop {
-                self.piece_row += 1;
-                if self.check_landing() {
-                    self.piece_row -= 1;
-                    break;
+    // Called whenever the piece moves or rotates, which may start the lock delay over
+    pub fn piece_moved(&mut self, config: &Config, now: u64) {
+        match config.lock_delay_mode {
+            LockDelayMode::StepReset => {},
+            LockDelayMode::MoveReset => {
+                // Only moves made on the ground count towards the limit
+                if self.lands_at(self.piece_row + 1) && self.lock_resets < MOVE_RESET_LIMIT {
+                    self.lock_resets += 1;
+                    self.lock_time = now;
                 }
-            }
-            for &(offset_row, offset_col) in piece.offset_map(self.rotation).iter() {
-                let mut color = piece.color();
-                color.a = 0.5;
-                draw_rectangle(
-                    x + (self.piece_col + offset_col) as f32 * tile_size() + grid_thickness() / 2.0,
-                    y + (self.piece_row + offset_row) as f32 * tile_size() + grid_thickness() / 2.0,
-                    tile_size() - grid_thickness(),
-                    tile_size() - grid_thickness(),
-                    color,
-                );
-            }
-            self.piece_row = old_piece_row;
-        }
-    }
-
-    fn draw_stats(&self, x: f32, y: f32, font: Font, stats: &Stats) {
-        draw_text_ex("STATS", x + margin(), y + tile_size(), text_large(font, WHITE));
-        draw_text_ex(&format!("Pieces: {}", stats.pieces), x + margin(),
-                y + text_size_large() + 2.0 * margin(), text_normal(font, WHITE));
-        draw_text_ex(&format!("Lines: {}", stats.lines), x + margin(),
-                y + text_size_large() + 2.0 * margin() + text_size_normal(), text_normal(font, WHITE));
-        draw_text_ex(&format!("Inputs: {}", stats.inputs), x + margin(),
-                y + text_size_large() + 2.0 * margin() + 2.0 * text_size_normal(), text_normal(font, WHITE));
-        draw_text_ex(&format!("Faults: {}", stats.faults), x + margin(),
-                y + text_size_large() + 2.0 * margin() + 3.0 * text_size_normal(), text_normal(font, WHITE));
-    }
-
-    fn draw_finesse_path(&self, x: f32, y: f32, font: Font) {
-        if let Some(path) = &self.finesse_path {
-            draw_text_ex(&format!("{:?}", self.prev_path), x + margin(),
-                    y + text_size_normal() + margin(), text_normal(font, Color::new(1.0, 0.5, 0.5, 1.0)));
-            draw_text_ex(&format!("{:?}", path), x + margin(),
-                    y + 2.0 * text_size_normal() + margin(), text_normal(font, Color::new(0.5, 1.0, 0.5, 1.0)));
+            },
+            LockDelayMode::Infinity => {
+                self.lock_time = now;
+            },
         }
     }
 
-    fn draw_pcs(&self, x: f32, y: f32, scale: f32, font: Font, stats: &Stats) {
-        draw_text_ex("PCs", x + margin(), y + tile_size(), text_large(font, WHITE));
-        let mut height = text_size_large() + 2.0 * margin();
-
-        let mut sorted = self.pcs.clone();
-        sorted.sort_by_key(|pc| pc.height());
-
-        for pc in sorted.iter() {
-            // Draw the PC strategy this will lead into afterwards
-            let next_pc_piece_num = ((stats.lines + pc.height() as u32) * 5 / 2) % 7 + 1;
-            height += text_size_small();
-            draw_text_ex(match next_pc_piece_num {
-                1 => "=> 1st PC",
-                2 => "=> 6th PC",
-                3 => "=> 4th PC",
-                4 => "=> 2nd PC",
-                5 => "=> 7th PC",
-                6 => "=> 5th PC",
-                7 => "=> 3rd PC / DPC",
-                _ => "",
-            }, x + margin(), y + height, text_small(font, WHITE));
-            height += margin();
-            // Draw the solution picture
-            let pc_height = pc.draw(x, y + height, scale);
-            height += pc_height + margin();
-            // Draw the order of piece placements
-            let sequence_height = pc.draw_sequence(x, y + height, scale * 0.25);
-            height += sequence_height + margin();
+    // Puts the current piece back at the top, as when it spawns or comes out of hold. The game is
+    // over if there's no room for it there.
+    pub fn move_to_spawn(&mut self, now: u64) {
+        self.piece_row = 1;
+        self.piece_col = 4;
+        self.rotation = Rotation::Normal;
+        self.last_kick = None;
+        self.lowest_row = self.piece_row;
+        self.lock_time = now;
+        self.lock_resets = 0;
+        if self.check_wall_intersect() {
+            self.top_out();
         }
     }
 
-    fn draw_setups(&mut self, x: f32, y: f32, scale: f32, font: Font, stats: &Stats) {
-        draw_text_ex("SETUPS", x + margin(), y + tile_size(), text_large(font, WHITE));
-        let mut height = text_size_large() + 2.0 * margin();
-
-        for setup in find_setups(self, stats).iter() {
-            // Draw the setup build picture
-            let setup_height = setup.draw(&self.board, x, y + height, scale, font);
-            height += setup_height + margin();
-        }
+    fn top_out(&mut self) {
+        self.game_over = true;
+        self.events.push(GameEvent::ToppedOut);
     }
 
-    fn draw_strategy(&self, x: f32, y: f32, font: Font) {
-        // If no PC is available, don't draw strategy text
-        if !self.board.is_empty() && self.pcs.len() == 0 {
+    fn apply_gravity(&mut self, config: &Config, stats: &mut Stats, now: u64) {
+        // Once on the ground, wait out the lock delay. This runs with gravity off too, so pieces
+        // can't sit on the stack forever.
+        if self.lands_at(self.piece_row + 1) {
+            // Under move reset, a piece that's used up its resets locks as soon as it touches down
+            let out_of_resets = config.lock_delay_mode == LockDelayMode::MoveReset && self.lock_resets >= MOVE_RESET_LIMIT;
+            if out_of_resets || now.saturating_sub(self.lock_time) > config.grace_period as u64 {
+                self.place_piece(config, stats);
+            }
             return;
         }
-        let piece_num = self.pc_piece_num % 7 + 1;
-        draw_text_ex(match piece_num {
-            1 => "1st PC",
-            2 => "6th PC",
-            3 => "4th PC",
-            4 => "2nd PC",
-            5 => "7th PC",
-            6 => "5th PC",
-            7 => "3rd PC / DPC",
-            _ => "",
-        }, x + margin(), y + tile_size(), text_normal(font, WHITE));
-    }
-
-    pub fn refresh_last_time(&mut self) {
-        self.last_time = Instant::now();
-    }
-
-    fn apply_gravity(&mut self, config: &Config, stats: &mut Stats) {
-        let now = Instant::now();
-        let fall_time = now.duration_since(self.last_time).as_millis() as u32;
-        // Check if we have already touched the ground -- wait until soft drop time elapses
-        if config.gravity > 0.0 {
-            self.piece_row += 1;
-            if self.check_landing() {
-                if fall_time > config.grace_period {
-                    self.piece_row -= 1;
-                    self.place_piece(stats);
-                } else {
-                    self.piece_row -= 1;
-                    return;
-                }
-            }
-            self.piece_row -= 1;
+        // Without gravity pieces only ever fall when the player drops them
+        if config.gravity <= 0.0 {
+            return;
         }
         // Otherwise, apply gravity
-        if fall_time >= (1000.0 / config.gravity) as u32 {
+        if now.saturating_sub(self.last_time) >= (1000.0 / config.gravity) as u64 {
             self.piece_row += 1;
-            self.refresh_last_time();
-            if self.check_landing() {
-                self.piece_row -= 1;
-                self.place_piece(stats);
-            }
+            self.refresh_last_time(now);
+            self.piece_fell(now);
         }
     }
 
     pub fn check_landing(&self) -> bool {
+        self.lands_at(self.piece_row)
+    }
+
+    // Returns true if the current piece would overlap the floor or the stack at the given row
+    pub fn lands_at(&self, piece_row: i8) -> bool {
         if let Some(piece) = self.piece {
             for &(offset_row, offset_col) in piece.offset_map(self.rotation).iter() {
-                let row = (self.piece_row + offset_row) as usize;
+                let row = (piece_row + offset_row) as usize;
                 let col = (self.piece_col + offset_col) as usize;
-                if row > 22 || self.board.tiles[row][col].piece.is_some() {
+                if row > 22 || self.board.tiles[row][col].is_filled() {
                     return true;
                 }
             }
@@ -307,12 +202,37 @@ impl Game {
         return false;
     }
 
+    // Returns the row the current piece would land on if it were hard dropped
+    pub fn shadow_row(&self) -> i8 {
+        let mut row = self.piece_row;
+        while !self.lands_at(row + 1) {
+            row += 1;
+        }
+        row
+    }
+
+    // Returns true if the current piece would be inside the board and clear of the stack if it
+    // were centered at the given position and rotation
+    pub fn fits_at(&self, piece_row: i8, piece_col: i8, rotation: Rotation) -> bool {
+        if let Some(piece) = self.piece {
+            for &(offset_row, offset_col) in piece.offset_map(rotation).iter() {
+                let row = piece_row + offset_row;
+                let col = piece_col + offset_col;
+                if col < 0 || col > 9 || row < 0 || row > 22 ||
+                        self.board.tiles[row as usize][col as usize].is_filled() {
+                    return false;
+                }
+            }
+        }
+        return true;
+    }
+
     pub fn check_wall_intersect(&self) -> bool {
         if let Some(piece) = self.piece {
             for &(offset_row, offset_col) in piece.offset_map(self.rotation).iter() {
                 let row = self.piece_row + offset_row;
                 let col = self.piece_col + offset_col;
-                if col < 0 || col > 9 || self.board.tiles[row as usize][col as usize].piece.is_some() {
+                if col < 0 || col > 9 || self.board.tiles[row as usize][col as usize].is_filled() {
                     return true;
                 }
             }
@@ -320,21 +240,30 @@ impl Game {
         return false;
     }
 
-    pub fn place_piece(&mut self, stats: &mut Stats) {
+    pub fn place_piece(&mut self, config: &Config, stats: &mut Stats) {
+        let placed = self.piece;
         // Calculate optimal finesse and number of faults
         self.prev_path = self.my_path.clone();
         self.my_path = Vec::new();
         let moves = self.prev_path.len();
+        let mut spin = Spin::None;
         if let Some(piece) = self.piece {
-            let (num_faults, path) = get_finesse_faults(&self.board, piece, moves as u8, self.piece_row as u8, self.piece_col as u8, self.rotation);
+            let board = BitBoard::from(&self.board);
+            let (num_faults, path) = get_finesse_faults(&board, config.rotation_system, piece, moves as u8, self.piece_row as u8, self.piece_col as u8, self.rotation);
             stats.faults += num_faults as u32;
             self.finesse_path = path;
+            // Has to be checked before the piece is on the board
+            spin = detect_spin(&board, piece, self.piece_row, self.piece_col, self.rotation, self.last_kick, config.all_spins);
         }
 
         self.prev_stats = *stats;
         // Saving stuff on undo stack
-        self.undo_stack.push((self.board, self.piece, self.hold, self.held, self.prev_stats, self.pc_piece_num));
+        self.undo_stack.push(self.undo_entry(self.prev_stats));
         stats.pieces += 1;
+        // A piece that locks entirely above the visible board tops out
+        let locked_out = self.piece.is_some_and(|piece| {
+            piece.offset_map(self.rotation).iter().all(|&(offset_row, _)| self.piece_row + offset_row < 3)
+        });
         // Actually placing the piece on the board
         if let Some(piece) = self.piece {
             for &(offset_row, offset_col) in piece.offset_map(self.rotation).iter() {
@@ -345,55 +274,464 @@ impl Game {
                 self.rotation = Rotation::Normal;
             }
         }
-        stats.lines += self.board.clear_lines() as u32;
+        let lines = self.board.clear_lines();
+        if let Some(piece) = placed {
+            stats.record_clear(ClearType::new(piece, spin, lines), self.board.is_empty(), config.attack_table);
+        }
         self.held = false;
         // If the board is clear now, update PC piece num
         if self.board.is_empty() {
             self.pc_piece_num = stats.pieces;
+            self.pc_start_hold = self.hold;
         } else {
             // Or, if we have cleared a multiple of 4 lines since the last PC -- e.g. on 1st PC recovery
             let pc_lines_diff = stats.lines - self.pc_piece_num * 2 / 5;
             if pc_lines_diff > 0 && pc_lines_diff % 4 == 0 {
                 self.pc_piece_num = stats.lines * 5 / 2;
+                self.pc_start_hold = self.hold;
             }
         }
+        if let Some(piece) = placed {
+            self.events.push(GameEvent::PiecePlaced(ClearType::new(piece, spin, lines)));
+        }
+        if locked_out {
+            self.top_out();
+        }
+    }
+
+    // Swaps in a different board, e.g. one pasted from a fumen, keeping the current piece, hold and
+    // queue. Earlier boards can't be undone back to since they no longer lead here. The piece starts
+    // over at the top, which tops out if the new board is in the way.
+    pub fn load_board(&mut self, board: Board, stats: &Stats, now: u64) {
+        if board.is_empty() {
+            self.pc_piece_num = stats.pieces;
+            self.pc_start_hold = self.hold;
+        }
+        self.replace_board(board, now);
+    }
+
+    // Saves the current position, along with the given stats, for undo to come back to
+    pub fn undo_entry(&self, stats: Stats) -> UndoEntry {
+        UndoEntry {
+            board: self.board,
+            piece: self.piece,
+            hold: self.hold,
+            held: self.held,
+            stats,
+            pc_piece_num: self.pc_piece_num,
+            pc_start_hold: self.pc_start_hold,
+        }
     }
 
-    pub fn refresh_pcs(
-        &self,
-        cancel_flag: &mut Option<Arc<AtomicBool>>,
-        tx: &Sender<Vec<Pc>>,
+    fn replace_board(&mut self, board: Board, now: u64) {
+        self.board = board;
+        self.undo_stack = Vec::new();
+        self.finesse_path = None;
+        self.my_path = Vec::new();
+        // A fresh position can be played on after topping out
+        self.game_over = false;
+        self.events.push(GameEvent::BoardLoaded);
+        self.refresh_last_time(now);
+        self.move_to_spawn(now);
+    }
+
+    // Starts play from a hand-made position, e.g. one drawn in the board editor. The given queue
+    // comes first, then fresh bags. The PC piece num says which PC of the loop this is part of.
+    pub fn load_position(
+        &mut self,
+        board: Board,
+        piece: Option<Piece>,
+        hold: Option<Piece>,
+        queue: VecDeque<Piece>,
+        pc_piece_num: u32,
+        now: u64,
     ) {
-        // Cancel previous solver if it's still running and run new solver
-        if let Some(flag) = cancel_flag.take() {
-            flag.store(true, Ordering::Relaxed);
+        self.piece = piece;
+        self.hold = hold;
+        self.held = false;
+        self.queue = queue;
+        self.randomizer = self.randomizer.fresh();
+        init_queue(self);
+        self.pc_piece_num = pc_piece_num;
+        self.pc_start_hold = hold;
+        self.replace_board(board, now);
+    }
+
+    fn spawn_if_needed(&mut self, config: &Config, now: u64) {
+        if self.piece.is_none() {
+            get_next_piece(self);
+            self.refresh_last_time(now);
+            self.move_to_spawn(now);
+            if self.game_over {
+                return;
+            }
+            self.das_cut_time = now + config.dcd as u64;
+            // Without carry-over, a direction still held has to charge DAS again for the new piece
+            if !config.das_carry {
+                if self.left_down {
+                    self.left_time = now;
+                    self.left_das_activated = false;
+                }
+                if self.right_down {
+                    self.right_time = now;
+                    self.right_das_activated = false;
+                }
+            }
+            if let Some(piece) = self.piece {
+                self.events.push(GameEvent::PieceSpawned(piece));
+            }
         }
-        *cancel_flag = Some(find_pcs(self.clone(), tx.clone()));
     }
 
+    // Advances held keys and gravity up to the given time
+    fn update(&mut self, config: &Config, stats: &mut Stats, now: u64) {
+        // Nothing moves once the game is over
+        if self.game_over {
+            return;
+        }
+        self.spawn_if_needed(config, now);
+        if self.game_over {
+            return;
+        }
+        handle_held_keys(config, self, now);
+        self.apply_gravity(config, stats, now);
+    }
+
+    // Feeds a batch of timestamped inputs into the game and advances it to `now`, returning
+    // everything that happened along the way. Inputs should be in time order and no later than
+    // `now`. DAS, ARR, DCD and gravity only advance inside this call, so it should be called regularly
+    // (e.g. once per frame) even when there are no new inputs.
     pub fn step(
         &mut self,
         config: &Config,
         stats: &mut Stats,
-        waiting: bool,
-        cancel_flag: &mut Option<Arc<AtomicBool>>,
-        rx: &Receiver<Vec<Pc>>,
-        tx: &Sender<Vec<Pc>>,
-    ) {
-        if self.piece.is_none() {
-            get_next_piece(self);
-            self.piece_row = 1;
-            self.piece_col = 4;
-            self.refresh_last_time();
-            self.refresh_pcs(cancel_flag, tx);
+        inputs: &[InputEvent],
+        now: u64,
+    ) -> Vec<GameEvent> {
+        for &input in inputs.iter() {
+            self.update(config, stats, input.time);
+            handle_event(config, stats, self, input);
         }
-        // Check if PC solutions have come in from the other thread
-        if let Ok(result) = rx.try_recv() {
-            // Removing duplicate solutions (different sequence but same solve)
-            let pc_set: HashSet<Pc> = result.into_iter().collect();
-            self.pcs = pc_set.into_iter().collect();
+        self.update(config, stats, now);
+        std::mem::take(&mut self.events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A game that has spawned its first piece, with handling that's easy to count steps with
+    fn start() -> (Game, Config, Stats) {
+        let config = Config {
+            das: 100,
+            arr: 20,
+            ..Config::default()
+        };
+        let mut game = Game::with_seed(1);
+        let mut stats = Stats::new();
+        game.step(&config, &mut stats, &[], 0);
+        (game, config, stats)
+    }
+
+    #[test]
+    fn first_step_spawns_a_piece() {
+        let mut game = Game::with_seed(1);
+        let events = game.step(&Config::default(), &mut Stats::new(), &[], 0);
+        let piece = game.piece.expect("No piece spawned");
+        assert_eq!(events, vec![GameEvent::PieceSpawned(piece)]);
+        assert_eq!((game.piece_row, game.piece_col), (1, 4));
+        assert_eq!(game.queue.len(), 5);
+    }
+
+    #[test]
+    fn same_seed_deals_the_same_pieces() {
+        let (a, _, _) = start();
+        let (b, _, _) = start();
+        assert_eq!(a.piece, b.piece);
+        assert_eq!(a.queue, b.queue);
+    }
+
+    #[test]
+    fn tap_moves_one_column() {
+        let (mut game, config, mut stats) = start();
+        game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::Left)], 10);
+        game.step(&config, &mut stats, &[InputEvent::release(20, KeyAction::Left)], 500);
+        assert_eq!(game.piece_col, 3);
+        game.step(&config, &mut stats, &[InputEvent::press(510, KeyAction::Right)], 510);
+        game.step(&config, &mut stats, &[InputEvent::release(520, KeyAction::Right)], 1000);
+        assert_eq!(game.piece_col, 4);
+    }
+
+    #[test]
+    fn das_waits_then_arr_repeats() {
+        let (mut game, config, mut stats) = start();
+        game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::Left)], 109);
+        assert_eq!(game.piece_col, 3);
+        // DAS charges 100ms after the press, then moves every 20ms
+        game.step(&config, &mut stats, &[], 110);
+        assert_eq!(game.piece_col, 2);
+        game.step(&config, &mut stats, &[], 129);
+        assert_eq!(game.piece_col, 2);
+        game.step(&config, &mut stats, &[], 130);
+        assert_eq!(game.piece_col, 1);
+    }
+
+    #[test]
+    fn zero_arr_shifts_to_the_wall() {
+        let (mut game, mut config, mut stats) = start();
+        config.arr = 0;
+        game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::Right)], 110);
+        assert!(game.piece_col > 5);
+        // Already against the wall, so holding it longer doesn't move it
+        let col = game.piece_col;
+        game.step(&config, &mut stats, &[], 200);
+        assert_eq!(game.piece_col, col);
+    }
+
+    #[test]
+    fn releasing_stops_das() {
+        let (mut game, config, mut stats) = start();
+        let inputs = [InputEvent::press(10, KeyAction::Left), InputEvent::release(50, KeyAction::Left)];
+        game.step(&config, &mut stats, &inputs, 500);
+        assert_eq!(game.piece_col, 3);
+        assert!(!game.left_down && !game.left_das_activated);
+    }
+
+    #[test]
+    fn most_recent_direction_wins() {
+        let (mut game, config, mut stats) = start();
+        let inputs = [InputEvent::press(10, KeyAction::Left), InputEvent::press(20, KeyAction::Right)];
+        game.step(&config, &mut stats, &inputs, 200);
+        // Left tapped once, then right took over and charged DAS
+        assert!(game.piece_col > 4);
+    }
+
+    #[test]
+    fn hard_drop_places_and_spawns_the_next_piece() {
+        let (mut game, config, mut stats) = start();
+        let placed = game.piece.unwrap();
+        let next = game.queue[0];
+        let events = game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::HardDrop)], 10);
+        assert_eq!(events[0], GameEvent::PiecePlaced(ClearType::new(placed, Spin::None, 0)));
+        assert_eq!(events[1], GameEvent::PieceSpawned(next));
+        assert_eq!(stats.pieces, 1);
+        assert!(game.board.tiles[22].iter().any(|tile| tile.piece.is_some()));
+        assert_eq!(game.piece, Some(next));
+        assert_eq!((game.piece_row, game.piece_col), (1, 4));
+    }
+
+    #[test]
+    fn hold_swaps_once_per_piece() {
+        let (mut game, config, mut stats) = start();
+        let first = game.piece;
+        let next = game.queue[0];
+        let events = game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::Hold)], 10);
+        assert_eq!(events, vec![GameEvent::Held, GameEvent::PieceSpawned(next)]);
+        assert_eq!(game.hold, first);
+        assert_eq!(game.piece, Some(next));
+        // Can't hold again until a piece is placed
+        let events = game.step(&config, &mut stats, &[InputEvent::press(20, KeyAction::Hold)], 20);
+        assert!(events.is_empty());
+        assert_eq!(game.hold, first);
+        assert_eq!(game.piece, Some(next));
+        // Placing lets the held piece be swapped back in
+        game.step(&config, &mut stats, &[InputEvent::press(30, KeyAction::HardDrop)], 30);
+        game.step(&config, &mut stats, &[InputEvent::press(40, KeyAction::Hold)], 40);
+        assert_eq!(game.piece, first);
+    }
+
+    #[test]
+    fn undo_takes_back_the_last_placement() {
+        let (mut game, config, mut stats) = start();
+        let first = game.piece;
+        let queue = game.queue.clone();
+        game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::HardDrop)], 10);
+        let events = game.step(&config, &mut stats, &[InputEvent::press(20, KeyAction::Undo)], 20);
+        assert_eq!(events, vec![GameEvent::Undone]);
+        assert!(game.board.is_empty());
+        assert_eq!(game.piece, first);
+        assert_eq!(game.queue.iter().take(4).collect::<Vec<_>>(), queue.iter().take(4).collect::<Vec<_>>());
+        assert_eq!(stats.pieces, 0);
+        // Nothing left to undo
+        let events = game.step(&config, &mut stats, &[InputEvent::press(30, KeyAction::Undo)], 30);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn reset_starts_a_new_game() {
+        let (mut game, config, mut stats) = start();
+        game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::HardDrop)], 10);
+        let events = game.step(&config, &mut stats, &[InputEvent::press(20, KeyAction::Reset)], 20);
+        assert_eq!(events[0], GameEvent::Reset);
+        assert!(game.board.is_empty());
+        assert_eq!(stats.pieces, 0);
+        assert!(game.undo_stack.is_empty());
+        assert!(game.piece.is_some());
+    }
+
+    #[test]
+    fn reset_replays_a_pinned_seed() {
+        let (mut game, mut config, mut stats) = start();
+        config.pin_seed = true;
+        let (piece, queue) = (game.piece, game.queue.clone());
+        game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::HardDrop)], 10);
+        game.step(&config, &mut stats, &[InputEvent::press(20, KeyAction::Reset)], 20);
+        assert_eq!(game.seed, 1);
+        assert_eq!((game.piece, game.queue.clone()), (piece, queue));
+    }
+
+    // Sets the piece down on the floor as a soft drop would, at the given time
+    fn drop_to_floor(game: &mut Game, now: u64) {
+        while !game.lands_at(game.piece_row + 1) {
+            game.piece_row += 1;
+        }
+        game.piece_fell(now);
+    }
+
+    // Taps left and right in turn at each of the given times
+    fn wiggle(game: &mut Game, config: &Config, stats: &mut Stats, times: impl Iterator<Item = u64>) {
+        for (i, time) in times.enumerate() {
+            let action = if i % 2 == 0 { KeyAction::Left } else { KeyAction::Right };
+            game.step(config, stats, &[InputEvent::press(time, action), InputEvent::release(time, action)], time);
+        }
+    }
+
+    // Fills the given rows of the board, leaving the leftmost column open so nothing clears
+    fn fill_rows(game: &mut Game, rows: std::ops::Range<usize>) {
+        for row in rows {
+            for col in 1..10 {
+                game.board.tiles[row][col] = Tile::from(Piece::I);
+            }
         }
-        handle_input(config, stats, self, waiting, cancel_flag, tx);
-        self.apply_gravity(config, stats);
+    }
+
+    #[test]
+    fn grounded_piece_locks_without_gravity() {
+        let (mut game, config, mut stats) = start();
+        assert_eq!(config.gravity, 0.0);
+        drop_to_floor(&mut game, 100);
+        game.step(&config, &mut stats, &[], 850);
+        assert_eq!(stats.pieces, 0);
+        let events = game.step(&config, &mut stats, &[], 851);
+        assert!(matches!(events[0], GameEvent::PiecePlaced(_)));
+        assert_eq!(stats.pieces, 1);
+    }
+
+    #[test]
+    fn floating_piece_never_locks_without_gravity() {
+        let (mut game, config, mut stats) = start();
+        game.step(&config, &mut stats, &[], 10_000);
+        assert_eq!(stats.pieces, 0);
+        assert_eq!(game.piece_row, 1);
+    }
+
+    #[test]
+    fn gravity_pulls_the_piece_down_then_locks_it() {
+        let (mut game, mut config, mut stats) = start();
+        config.gravity = 100.0;
+        for time in (0..=500).step_by(10) {
+            game.step(&config, &mut stats, &[], time);
+        }
+        assert!(game.lands_at(game.piece_row + 1));
+        game.step(&config, &mut stats, &[], 2000);
+        assert_eq!(stats.pieces, 1);
+    }
+
+    #[test]
+    fn step_reset_ignores_moves() {
+        let (mut game, mut config, mut stats) = start();
+        config.lock_delay_mode = LockDelayMode::StepReset;
+        drop_to_floor(&mut game, 0);
+        wiggle(&mut game, &config, &mut stats, (100..=700).step_by(100));
+        assert_eq!(stats.pieces, 0);
+        game.step(&config, &mut stats, &[], 751);
+        assert_eq!(stats.pieces, 1);
+    }
+
+    #[test]
+    fn move_reset_restarts_the_delay_on_moves() {
+        let (mut game, mut config, mut stats) = start();
+        config.lock_delay_mode = LockDelayMode::MoveReset;
+        drop_to_floor(&mut game, 0);
+        wiggle(&mut game, &config, &mut stats, (500..=1000).step_by(500));
+        game.step(&config, &mut stats, &[], 1750);
+        assert_eq!(stats.pieces, 0);
+        game.step(&config, &mut stats, &[], 1751);
+        assert_eq!(stats.pieces, 1);
+    }
+
+    #[test]
+    fn move_reset_locks_once_resets_run_out() {
+        let (mut game, mut config, mut stats) = start();
+        config.lock_delay_mode = LockDelayMode::MoveReset;
+        drop_to_floor(&mut game, 0);
+        let limit = MOVE_RESET_LIMIT as u64;
+        wiggle(&mut game, &config, &mut stats, (1..limit).map(|i| i * 10));
+        assert_eq!(stats.pieces, 0);
+        // The last reset locks the piece straight away since it's still on the ground
+        wiggle(&mut game, &config, &mut stats, std::iter::once(limit * 10));
+        assert_eq!(stats.pieces, 1);
+    }
+
+    #[test]
+    fn move_resets_come_back_on_a_new_lowest_row() {
+        let (mut game, mut config, mut stats) = start();
+        config.lock_delay_mode = LockDelayMode::MoveReset;
+        game.piece_row += 1;
+        game.piece_fell(0);
+        game.lock_resets = MOVE_RESET_LIMIT;
+        drop_to_floor(&mut game, 10);
+        assert_eq!(game.lock_resets, 0);
+        game.step(&config, &mut stats, &[], 20);
+        assert_eq!(stats.pieces, 0);
+    }
+
+    #[test]
+    fn infinity_never_locks_while_moving() {
+        let (mut game, mut config, mut stats) = start();
+        config.lock_delay_mode = LockDelayMode::Infinity;
+        drop_to_floor(&mut game, 0);
+        wiggle(&mut game, &config, &mut stats, (1..=100).map(|i| i * 500));
+        assert_eq!(stats.pieces, 0);
+        game.step(&config, &mut stats, &[], 50_751);
+        assert_eq!(stats.pieces, 1);
+    }
+
+    #[test]
+    fn spawning_into_the_stack_tops_out() {
+        let mut game = Game::with_seed(1);
+        let config = Config::default();
+        let mut stats = Stats::new();
+        fill_rows(&mut game, 0..23);
+        let events = game.step(&config, &mut stats, &[], 0);
+        assert_eq!(events, vec![GameEvent::ToppedOut]);
+        assert!(game.game_over);
+        // Inputs do nothing once the game is over
+        let events = game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::HardDrop)], 10);
+        assert!(events.is_empty());
+        assert_eq!(stats.pieces, 0);
+    }
+
+    #[test]
+    fn locking_above_the_board_tops_out() {
+        let (mut game, config, mut stats) = start();
+        fill_rows(&mut game, 3..23);
+        let events = game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::HardDrop)], 10);
+        assert!(matches!(events[0], GameEvent::PiecePlaced(_)));
+        assert_eq!(events[1], GameEvent::ToppedOut);
+        assert!(game.game_over);
+        assert_eq!(stats.pieces, 1);
+    }
+
+    #[test]
+    fn undo_after_topping_out_resumes_play() {
+        let (mut game, config, mut stats) = start();
+        fill_rows(&mut game, 3..23);
+        game.step(&config, &mut stats, &[InputEvent::press(10, KeyAction::HardDrop)], 10);
+        game.step(&config, &mut stats, &[InputEvent::press(20, KeyAction::Undo)], 20);
+        assert!(!game.game_over);
+        assert!(game.piece.is_some());
+        assert_eq!(stats.pieces, 0);
     }
 }
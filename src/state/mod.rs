@@ -2,10 +2,12 @@ mod board;
 mod game;
 mod piece;
 mod rotation;
+mod rotation_system;
 mod tile;
 
 pub use board::*;
 pub use game::*;
 pub use piece::*;
 pub use rotation::*;
+pub use rotation_system::*;
 pub use tile::*;
@@ -1,8 +1,8 @@
-use macroquad::prelude::*;
-use crate::util::window::tile_size;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
 use super::Rotation;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, EnumIter)]
 pub enum Piece {
     I,
     J,
@@ -14,55 +14,17 @@ pub enum Piece {
 }
 
 impl Piece {
-    pub fn color(&self) -> Color {
-        match self {
-            Piece::I => Color::from_hex(0x4dcdd1),
-            Piece::J => Color::from_hex(0x4d70d1),
-            Piece::L => Color::from_hex(0xdb9b4d),
-            Piece::O => Color::from_hex(0xdbcd4d),
-            Piece::S => Color::from_hex(0x63e06f),
-            Piece::T => Color::from_hex(0xdd87e0),
-            Piece::Z => Color::from_hex(0xe06363),
-        }
-    }
-
-    pub fn draw(&self, x: f32, y: f32, scale: f32) -> (f32, f32) {
-        let unit = tile_size() * scale;
-
-        match self {
-            Piece::I => {
-                draw_rectangle(x, y, 4.0 * unit, unit, self.color());
-                (4.0 * unit, unit)
-            },
-            Piece::J => {
-                draw_rectangle(x, y, unit, unit, self.color());
-                draw_rectangle(x, y + unit, 3.0 * unit, unit, self.color());
-                (3.0 * unit, 2.0 * unit)
-            },
-            Piece::L => {
-                draw_rectangle(x + 2.0 * unit, y, unit, unit, self.color());
-                draw_rectangle(x, y + unit, 3.0 * unit, unit, self.color());
-                (3.0 * unit, 2.0 * unit)
-            },
-            Piece::O => {
-                draw_rectangle(x, y, 2.0 * unit, 2.0 * unit, self.color());
-                (2.0 * unit, 2.0 * unit)
-            },
-            Piece::S => {
-                draw_rectangle(x + unit, y, 2.0 * unit, unit, self.color());
-                draw_rectangle(x, y + unit, 2.0 * unit, unit, self.color());
-                (3.0 * unit, 2.0 * unit)
-            },
-            Piece::T => {
-                draw_rectangle(x + unit, y, unit, unit, self.color());
-                draw_rectangle(x, y + unit, 3.0 * unit, unit, self.color());
-                (3.0 * unit, 2.0 * unit)
-            },
-            Piece::Z => {
-                draw_rectangle(x, y, 2.0 * unit, unit, self.color());
-                draw_rectangle(x + unit, y + unit, 2.0 * unit, unit, self.color());
-                (3.0 * unit, 2.0 * unit)
-            },
+    // Reads a piece letter, in either case
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'I' => Some(Piece::I),
+            'J' => Some(Piece::J),
+            'L' => Some(Piece::L),
+            'O' => Some(Piece::O),
+            'S' => Some(Piece::S),
+            'T' => Some(Piece::T),
+            'Z' => Some(Piece::Z),
+            _ => None,
         }
     }
 
@@ -122,51 +84,15 @@ impl Piece {
         }
     }
 
-    pub fn kick_map(&self, old_rot: Rotation, new_rot: Rotation) -> Vec<(i8, i8)> {
-        match self {
-            Piece::I => {
-                match (old_rot, new_rot) {
-                    (Rotation::Normal, Rotation::Normal) => vec![(0, 0)],
-                    (Rotation::Normal, Rotation::Cw) => vec![(0, 0), (0, -2), (0, 1), (1, -2), (-1, 2)],
-                    (Rotation::Normal, Rotation::Ccw) => vec![(0, 0), (0, -1), (0, 2), (-2, -1), (-2, 1)],
-                    (Rotation::Normal, Rotation::Flip) => vec![(0, 0)],
-                    (Rotation::Cw, Rotation::Normal) => vec![(0, 0), (0, 1), (0, -2), (2, 1), (-1, 2)],
-                    (Rotation::Cw, Rotation::Cw) => vec![(0, 0)],
-                    (Rotation::Cw, Rotation::Ccw) => vec![(0, 0)],
-                    (Rotation::Cw, Rotation::Flip) => vec![(0, 0), (0, -1), (0, 2), (-2, -1), (1, 2)],
-                    (Rotation::Ccw, Rotation::Normal) => vec![(0, 0), (0, 1), (0, -2), (2, 1), (-1, -2)],
-                    (Rotation::Ccw, Rotation::Cw) => vec![(0, 0)],
-                    (Rotation::Ccw, Rotation::Ccw) => vec![(0, 0)],
-                    (Rotation::Ccw, Rotation::Flip) => vec![(0, 0), (0, -2), (0, 1), (1, -2), (-2, 1)],
-                    (Rotation::Flip, Rotation::Normal) => vec![(0, 0)],
-                    (Rotation::Flip, Rotation::Cw) => vec![(0, 0), (0, 1), (0, -2), (2, 1), (-1, -2)],
-                    (Rotation::Flip, Rotation::Ccw) => vec![(0, 0), (0, 2), (0, -1), (-1, 2), (2, -1)],
-                    (Rotation::Flip, Rotation::Flip) => vec![(0, 0)],
-                }
-            }
-            Piece::J | Piece::L | Piece::S | Piece::T | Piece::Z => {
-                match (old_rot, new_rot) {
-                    (Rotation::Normal, Rotation::Normal) => vec![(0, 0)],
-                    (Rotation::Normal, Rotation::Cw) => vec![(0, 0), (0, -1), (-1, -1), (2, 0), (2, -1)],
-                    (Rotation::Normal, Rotation::Ccw) => vec![(0, 0), (0, 1), (-1, 1), (2, 0), (2, 1)],
-                    (Rotation::Normal, Rotation::Flip) => vec![(0, 0), (-1, 0), (-1, 1), (-1, -1), (0, 1), (0, -1)],
-                    (Rotation::Cw, Rotation::Normal) => vec![(0, 0), (0, 1), (1, 1), (-2, 0), (-2, 1)],
-                    (Rotation::Cw, Rotation::Cw) => vec![(0, 0)],
-                    (Rotation::Cw, Rotation::Ccw) => vec![(0, 0), (0, 1), (-2, 1), (-1, 1), (-2, 0), (-1, 0)],
-                    (Rotation::Cw, Rotation::Flip) => vec![(0, 0), (0, 1), (1, 1), (-2, 0), (-2, 1)],
-                    (Rotation::Ccw, Rotation::Normal) => vec![(0, 0), (0, -1), (1, -1), (-2, 0), (-2, -1)],
-                    (Rotation::Ccw, Rotation::Cw) => vec![(0, 0), (0, -1), (-2, -1), (-1, -1), (-2, 0), (-1, 0)],
-                    (Rotation::Ccw, Rotation::Ccw) => vec![(0, 0)],
-                    (Rotation::Ccw, Rotation::Flip) => vec![(0, 0), (0, -1), (1, -1), (-2, 0), (-2, -1)],
-                    (Rotation::Flip, Rotation::Normal) => vec![(0, 0), (1, 0), (1, -1), (1, 1), (0, -1), (0, 1)],
-                    (Rotation::Flip, Rotation::Cw) => vec![(0, 0), (0, -1), (-1, -1), (2, 0), (2, -1)],
-                    (Rotation::Flip, Rotation::Ccw) => vec![(0, 0), (0, 1), (-1, 1), (2, 0), (2, 1)],
-                    (Rotation::Flip, Rotation::Flip) => vec![(0, 0)],
-                }
-            }
-            Piece::O => {
-                vec![(0, 0)]
-            }
+    // The I piece's offset maps aren't all centered on the same cell, so this is where its center
+    // sits in each rotation relative to spawn -- rotating moves the center by the difference
+    pub fn rotation_center(&self, rotation: Rotation) -> (i8, i8) {
+        match (self, rotation) {
+            (Piece::I, Rotation::Normal) => (0, 0),
+            (Piece::I, Rotation::Cw) => (0, 1),
+            (Piece::I, Rotation::Flip) => (1, 1),
+            (Piece::I, Rotation::Ccw) => (1, 0),
+            _ => (0, 0),
         }
     }
 }
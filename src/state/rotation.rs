@@ -1,7 +1,37 @@
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+use serde::{Deserialize, Serialize};
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Rotation {
     Normal,
     Cw,
     Ccw,
     Flip,
 }
+
+impl Rotation {
+    pub fn cw(&self) -> Self {
+        match self {
+            Rotation::Normal => Rotation::Cw,
+            Rotation::Cw => Rotation::Flip,
+            Rotation::Ccw => Rotation::Normal,
+            Rotation::Flip => Rotation::Ccw,
+        }
+    }
+
+    pub fn ccw(&self) -> Self {
+        match self {
+            Rotation::Normal => Rotation::Ccw,
+            Rotation::Cw => Rotation::Normal,
+            Rotation::Ccw => Rotation::Flip,
+            Rotation::Flip => Rotation::Cw,
+        }
+    }
+
+    pub fn flip(&self) -> Self {
+        match self {
+            Rotation::Normal => Rotation::Flip,
+            Rotation::Cw => Rotation::Ccw,
+            Rotation::Ccw => Rotation::Cw,
+            Rotation::Flip => Rotation::Normal,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+use super::{Piece, Rotation};
+
+// Decides which kicks get tried when rotating. Both gameplay and the search code go through this so
+// that finesse and PC results match the game being trained for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+pub enum RotationSystem {
+    Srs, // Guideline SRS, 180 rotations don't kick
+    SrsPlus, // TETR.IO SRS+ -- symmetric I kicks and 180 kicks
+    SrsX, // SRS with the SRS-X 180 kick table
+    Ars, // Arika-style -- only tries one to the right and one to the left, I never kicks
+    NoKick, // Rotation only succeeds in place
+}
+
+impl RotationSystem {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RotationSystem::Srs => "SRS",
+            RotationSystem::SrsPlus => "SRS+",
+            RotationSystem::SrsX => "SRS-X",
+            RotationSystem::Ars => "ARS",
+            RotationSystem::NoKick => "No kicks",
+        }
+    }
+
+    // Returns the (row, col) offsets to try in order when rotating between these rotations
+    pub fn kicks(&self, piece: Piece, old_rot: Rotation, new_rot: Rotation) -> &'static [(i8, i8)] {
+        if old_rot == new_rot || piece == Piece::O {
+            return &[(0, 0)];
+        }
+        let is_180 = old_rot.flip() == new_rot;
+        match self {
+            RotationSystem::Srs => {
+                if is_180 {
+                    &[(0, 0)]
+                } else {
+                    srs_kicks(piece, old_rot, new_rot)
+                }
+            },
+            RotationSystem::SrsPlus => srs_plus_kicks(piece, old_rot, new_rot),
+            RotationSystem::SrsX => {
+                if is_180 {
+                    srs_x_180_kicks(old_rot)
+                } else {
+                    srs_kicks(piece, old_rot, new_rot)
+                }
+            },
+            RotationSystem::Ars => {
+                if piece == Piece::I {
+                    &[(0, 0)]
+                } else {
+                    &[(0, 0), (0, 1), (0, -1)]
+                }
+            },
+            RotationSystem::NoKick => &[(0, 0)],
+        }
+    }
+
+    // Attempts a rotation of the piece centered at (row, col), where `fits` says whether the piece
+    // would fit in the new rotation centered at a given (row, col). Returns the new center and the
+    // index of the kick that worked, or None if every kick failed.
+    pub fn rotate(
+        &self,
+        piece: Piece,
+        row: i8,
+        col: i8,
+        old_rot: Rotation,
+        new_rot: Rotation,
+        fits: impl Fn(i8, i8) -> bool,
+    ) -> Option<(i8, i8, usize)> {
+        let (old_center_row, old_center_col) = piece.rotation_center(old_rot);
+        let (new_center_row, new_center_col) = piece.rotation_center(new_rot);
+        let row = row + new_center_row - old_center_row;
+        let col = col + new_center_col - old_center_col;
+
+        for (index, &(kick_row, kick_col)) in self.kicks(piece, old_rot, new_rot).iter().enumerate() {
+            if fits(row + kick_row, col + kick_col) {
+                return Some((row + kick_row, col + kick_col, index));
+            }
+        }
+        None
+    }
+}
+
+fn srs_kicks(piece: Piece, old_rot: Rotation, new_rot: Rotation) -> &'static [(i8, i8)] {
+    match piece {
+        Piece::I => {
+            match (old_rot, new_rot) {
+                (Rotation::Normal, Rotation::Cw) => &[(0, 0), (0, -2), (0, 1), (1, -2), (-2, 1)],
+                (Rotation::Normal, Rotation::Ccw) => &[(0, 0), (0, -1), (0, 2), (-2, -1), (1, 2)],
+                (Rotation::Cw, Rotation::Normal) => &[(0, 0), (0, 2), (0, -1), (-1, 2), (2, -1)],
+                (Rotation::Cw, Rotation::Flip) => &[(0, 0), (0, -1), (0, 2), (-2, -1), (1, 2)],
+                (Rotation::Ccw, Rotation::Normal) => &[(0, 0), (0, 1), (0, -2), (2, 1), (-1, -2)],
+                (Rotation::Ccw, Rotation::Flip) => &[(0, 0), (0, -2), (0, 1), (1, -2), (-2, 1)],
+                (Rotation::Flip, Rotation::Cw) => &[(0, 0), (0, 1), (0, -2), (2, 1), (-1, -2)],
+                (Rotation::Flip, Rotation::Ccw) => &[(0, 0), (0, 2), (0, -1), (-1, 2), (2, -1)],
+                _ => &[(0, 0)],
+            }
+        },
+        _ => srs_plus_kicks(piece, old_rot, new_rot),
+    }
+}
+
+fn srs_plus_kicks(piece: Piece, old_rot: Rotation, new_rot: Rotation) -> &'static [(i8, i8)] {
+    match piece {
+        Piece::I => {
+            match (old_rot, new_rot) {
+                (Rotation::Normal, Rotation::Cw) => &[(0, 0), (0, -2), (0, 1), (1, -2), (-1, 2)],
+                (Rotation::Normal, Rotation::Ccw) => &[(0, 0), (0, -1), (0, 2), (-2, -1), (-2, 1)],
+                (Rotation::Cw, Rotation::Normal) => &[(0, 0), (0, 1), (0, -2), (2, 1), (-1, 2)],
+                (Rotation::Cw, Rotation::Flip) => &[(0, 0), (0, -1), (0, 2), (-2, -1), (1, 2)],
+                (Rotation::Ccw, Rotation::Normal) => &[(0, 0), (0, 1), (0, -2), (2, 1), (-1, -2)],
+                (Rotation::Ccw, Rotation::Flip) => &[(0, 0), (0, -2), (0, 1), (1, -2), (-2, 1)],
+                (Rotation::Flip, Rotation::Cw) => &[(0, 0), (0, 1), (0, -2), (2, 1), (-1, -2)],
+                (Rotation::Flip, Rotation::Ccw) => &[(0, 0), (0, 2), (0, -1), (-1, 2), (2, -1)],
+                _ => &[(0, 0)],
+            }
+        },
+        _ => {
+            match (old_rot, new_rot) {
+                (Rotation::Normal, Rotation::Cw) => &[(0, 0), (0, -1), (-1, -1), (2, 0), (2, -1)],
+                (Rotation::Normal, Rotation::Ccw) => &[(0, 0), (0, 1), (-1, 1), (2, 0), (2, 1)],
+                (Rotation::Normal, Rotation::Flip) => &[(0, 0), (-1, 0), (-1, 1), (-1, -1), (0, 1), (0, -1)],
+                (Rotation::Cw, Rotation::Normal) => &[(0, 0), (0, 1), (1, 1), (-2, 0), (-2, 1)],
+                (Rotation::Cw, Rotation::Ccw) => &[(0, 0), (0, 1), (-2, 1), (-1, 1), (-2, 0), (-1, 0)],
+                (Rotation::Cw, Rotation::Flip) => &[(0, 0), (0, 1), (1, 1), (-2, 0), (-2, 1)],
+                (Rotation::Ccw, Rotation::Normal) => &[(0, 0), (0, -1), (1, -1), (-2, 0), (-2, -1)],
+                (Rotation::Ccw, Rotation::Cw) => &[(0, 0), (0, -1), (-2, -1), (-1, -1), (-2, 0), (-1, 0)],
+                (Rotation::Ccw, Rotation::Flip) => &[(0, 0), (0, -1), (1, -1), (-2, 0), (-2, -1)],
+                (Rotation::Flip, Rotation::Normal) => &[(0, 0), (1, 0), (1, -1), (1, 1), (0, -1), (0, 1)],
+                (Rotation::Flip, Rotation::Cw) => &[(0, 0), (0, -1), (-1, -1), (2, 0), (2, -1)],
+                (Rotation::Flip, Rotation::Ccw) => &[(0, 0), (0, 1), (-1, 1), (2, 0), (2, 1)],
+                _ => &[(0, 0)],
+            }
+        },
+    }
+}
+
+// Same table for every piece
+fn srs_x_180_kicks(old_rot: Rotation) -> &'static [(i8, i8)] {
+    match old_rot {
+        Rotation::Normal => &[(0, 0), (0, 1), (0, 2), (-1, 1), (-1, 2), (0, -1), (0, -2), (-1, -1), (-1, -2), (1, 0), (0, 3), (0, -3)],
+        Rotation::Cw => &[(0, 0), (-1, 0), (-2, 0), (-1, -1), (-2, -1), (1, 0), (2, 0), (1, -1), (2, -1), (0, 1), (-3, 0), (3, 0)],
+        Rotation::Flip => &[(0, 0), (0, -1), (0, -2), (1, -1), (1, -2), (0, 1), (0, 2), (1, 1), (1, 2), (-1, 0), (0, -3), (0, 3)],
+        Rotation::Ccw => &[(0, 0), (-1, 0), (-2, 0), (-1, 1), (-2, 1), (1, 0), (2, 0), (1, 1), (2, 1), (0, -1), (-3, 0), (3, 0)],
+    }
+}
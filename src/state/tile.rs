@@ -1,29 +1,35 @@
-use macroquad::prelude::*;
 use crate::state::Piece;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Tile {
     pub piece: Option<Piece>,
+    // Gray cells that didn't come from a placed piece, e.g. garbage loaded from a fumen
+    pub garbage: bool,
 }
 
 impl Tile {
     pub fn new() -> Self {
         Self {
             piece: None,
+            garbage: false,
         }
     }
 
     pub fn from(piece: Piece) -> Self {
         Self {
             piece: Some(piece),
+            garbage: false,
         }
     }
 
-    pub fn color(&self) -> Color {
-        if let Some(piece) = self.piece {
-            piece.color()
-        } else {
-            BLACK
+    pub fn garbage() -> Self {
+        Self {
+            piece: None,
+            garbage: true,
         }
     }
+
+    pub fn is_filled(&self) -> bool {
+        self.piece.is_some() || self.garbage
+    }
 }
@@ -0,0 +1,242 @@
+use macroquad::prelude::*;
+use crate::search::{Pc, Placement};
+use crate::setups::PcSetup;
+use crate::state::{Board, Piece, Tile};
+use crate::util::font::text_small;
+use crate::util::window::*;
+
+pub fn piece_color(piece: Piece) -> Color {
+    match piece {
+        Piece::I => Color::from_hex(0x4dcdd1),
+        Piece::J => Color::from_hex(0x4d70d1),
+        Piece::L => Color::from_hex(0xdb9b4d),
+        Piece::O => Color::from_hex(0xdbcd4d),
+        Piece::S => Color::from_hex(0x63e06f),
+        Piece::T => Color::from_hex(0xdd87e0),
+        Piece::Z => Color::from_hex(0xe06363),
+    }
+}
+
+// Draws the piece flat side down, e.g. in the queue. Returns the width and height drawn.
+pub fn draw_piece_preview(piece: Piece, x: f32, y: f32, scale: f32) -> (f32, f32) {
+    let unit = tile_size() * scale;
+
+    match piece {
+        Piece::I => {
+            draw_rectangle(x, y, 4.0 * unit, unit, piece_color(piece));
+            (4.0 * unit, unit)
+        },
+        Piece::J => {
+            draw_rectangle(x, y, unit, unit, piece_color(piece));
+            draw_rectangle(x, y + unit, 3.0 * unit, unit, piece_color(piece));
+            (3.0 * unit, 2.0 * unit)
+        },
+        Piece::L => {
+            draw_rectangle(x + 2.0 * unit, y, unit, unit, piece_color(piece));
+            draw_rectangle(x, y + unit, 3.0 * unit, unit, piece_color(piece));
+            (3.0 * unit, 2.0 * unit)
+        },
+        Piece::O => {
+            draw_rectangle(x, y, 2.0 * unit, 2.0 * unit, piece_color(piece));
+            (2.0 * unit, 2.0 * unit)
+        },
+        Piece::S => {
+            draw_rectangle(x + unit, y, 2.0 * unit, unit, piece_color(piece));
+            draw_rectangle(x, y + unit, 2.0 * unit, unit, piece_color(piece));
+            (3.0 * unit, 2.0 * unit)
+        },
+        Piece::T => {
+            draw_rectangle(x + unit, y, unit, unit, piece_color(piece));
+            draw_rectangle(x, y + unit, 3.0 * unit, unit, piece_color(piece));
+            (3.0 * unit, 2.0 * unit)
+        },
+        Piece::Z => {
+            draw_rectangle(x, y, 2.0 * unit, unit, piece_color(piece));
+            draw_rectangle(x + unit, y + unit, 2.0 * unit, unit, piece_color(piece));
+            (3.0 * unit, 2.0 * unit)
+        },
+    }
+}
+
+pub fn tile_color(tile: &Tile) -> Color {
+    if let Some(piece) = tile.piece {
+        piece_color(piece)
+    } else if tile.garbage {
+        Color::from_hex(0x9a9a9a)
+    } else {
+        BLACK
+    }
+}
+
+pub fn draw_board(board: &Board, x: f32, y: f32) {
+    for r in 0..23 {
+        for c in 0..10 {
+            draw_rectangle(
+                x + c as f32 * tile_size() + grid_thickness() / 2.0,
+                y + r as f32 * tile_size() + grid_thickness() / 2.0,
+                tile_size() - grid_thickness(),
+                tile_size() - grid_thickness(),
+                tile_color(&board.tiles[r][c]),
+            );
+        }
+    }
+}
+
+pub fn draw_board_grid(x: f32, y: f32) {
+    // Horizontal grid lines
+    for r in 3..=23 {
+        draw_line(
+            x,
+            y + r as f32 * tile_size(),
+            x + board_width(),
+            y + r as f32 * tile_size(),
+            grid_thickness(),
+            if r == 3 || r == 23 {
+                WHITE
+            } else {
+                Color::new(0.2, 0.2, 0.2, 1.0)
+            },
+        );
+    }
+    // Vertical grid lines
+    for c in 0..=10 {
+        draw_line(
+            x + c as f32 * tile_size(),
+            y + 3.0 * tile_size() + grid_thickness() / 2.0,
+            x + c as f32 * tile_size(),
+            y + board_height() - grid_thickness() / 2.0,
+            grid_thickness(),
+            if c == 0 || c == 10 {
+                WHITE
+            } else {
+                Color::new(0.2, 0.2, 0.2, 1.0)
+            },
+        );
+    }
+}
+
+// Returns the total height of the drawn PC
+pub fn draw_pc(pc: &Pc, x: f32, y: f32, scale: f32) -> f32 {
+    let (final_board, cleared) = pc.get_final_board();
+    // Draw the board
+    for r in (23 - cleared)..23 {
+        for c in 0..10 {
+            // If the piece has already been placed, draw as gray to make solution easier to read
+            if pc.board().tiles[r][c].is_filled() {
+                draw_rectangle(
+                    x + tile_size() * scale * c as f32,
+                    y + tile_size() * scale * (r - (23 - cleared)) as f32,
+                    tile_size() * scale,
+                    tile_size() * scale,
+                    GRAY,
+                );
+                continue;
+            }
+            draw_rectangle(
+                x + tile_size() * scale * c as f32,
+                y + tile_size() * scale * (r - (23 - cleared)) as f32,
+                tile_size() * scale,
+                tile_size() * scale,
+                tile_color(&final_board.tiles[r][c]),
+            );
+        }
+    }
+    cleared as f32 * tile_size() * scale
+}
+
+// Draws the order that the pieces are placed, returns height of drawing
+pub fn draw_pc_sequence(pc: &Pc, x: f32, y: f32, scale: f32) -> f32 {
+    let tile = tile_size() * scale;
+    let margin = tile_size() * 0.25;
+    let mut width: f32 = margin;
+
+    for &placement in pc.placements().iter() {
+        match placement {
+            Placement::Hold => {},
+            Placement::Place { piece, .. } => {
+                match piece {
+                    Piece::I => {
+                        draw_rectangle(x + width, y + 0.5 * tile, 4.0 * tile, tile, piece_color(Piece::I));
+                        width += 4.0 * tile + margin;
+                    },
+                    Piece::J => {
+                        draw_rectangle(x + width, y, tile, tile, piece_color(Piece::J));
+                        draw_rectangle(x + width, y + tile, 3.0 * tile, tile, piece_color(Piece::J));
+                        width += 3.0 * tile + margin;
+                    },
+                    Piece::L => {
+                        draw_rectangle(x + width + 2.0 * tile, y, tile, tile, piece_color(Piece::L));
+                        draw_rectangle(x + width, y + tile, 3.0 * tile, tile, piece_color(Piece::L));
+                        width += 3.0 * tile + margin;
+                    },
+                    Piece::O => {
+                        draw_rectangle(x + width, y, 2.0 * tile, 2.0 * tile, piece_color(Piece::O));
+                        width += 2.0 * tile + margin;
+                    },
+                    Piece::S => {
+                        draw_rectangle(x + width + tile, y, 2.0 * tile, tile, piece_color(Piece::S));
+                        draw_rectangle(x + width, y + tile, 2.0 * tile, tile, piece_color(Piece::S));
+                        width += 3.0 * tile + margin;
+                    },
+                    Piece::T => {
+                        draw_rectangle(x + width + tile, y, tile, tile, piece_color(Piece::T));
+                        draw_rectangle(x + width, y + tile, 3.0 * tile, tile, piece_color(Piece::T));
+                        width += 3.0 * tile + margin;
+                    },
+                    Piece::Z => {
+                        draw_rectangle(x + width, y, 2.0 * tile, tile, piece_color(Piece::Z));
+                        draw_rectangle(x + width + tile, y + tile, 2.0 * tile, tile, piece_color(Piece::Z));
+                        width += 3.0 * tile + margin;
+                    },
+                }
+            },
+        }
+    }
+    2.0 * tile
+}
+
+// Returns the height of the board diagram drawn
+pub fn draw_setup(setup: &PcSetup, board: &Board, chance: Option<f32>, x: f32, y: f32, scale: f32, font: Font) -> f32 {
+    // Draw name, along with the solve chance once it has been worked out
+    let mut height = text_size_small() + margin();
+    let label = match chance {
+        Some(percent) => format!("{} ({:.1}%)", setup.name, percent),
+        None => setup.name.clone(),
+    };
+    draw_text_ex(&label, x + margin(), y + height, text_small(font, WHITE));
+    height += margin();
+
+    // First, construct final board position
+    let final_board = setup.get_final_board(board);
+    // Then, find the highest row in the board from which to start drawing
+    let mut start_row = 0;
+    'row: for r in 0..23 {
+        for c in 0..10 {
+            if final_board.tiles[r][c].is_filled() {
+                start_row = r;
+                break 'row;
+            }
+        }
+    }
+    // Finally, do the board drawing
+    for r in start_row..23 {
+        for c in 0..10 {
+            if final_board.tiles[r][c].is_filled() {
+                // If the mino was already there in the original board, draw in gray
+                let color = if board.tiles[r][c].is_filled() {
+                    GRAY
+                } else {
+                    tile_color(&final_board.tiles[r][c])
+                };
+                draw_rectangle(
+                    x + tile_size() * scale * c as f32,
+                    y + height + tile_size() * scale * (r - start_row) as f32,
+                    tile_size() * scale,
+                    tile_size() * scale,
+                    color,
+                );
+            }
+        }
+    }
+    (23 - start_row) as f32 * scale * tile_size() + height
+}
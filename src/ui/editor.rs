@@ -0,0 +1,89 @@
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+use crate::setups::PcNumber;
+use crate::state::{Board, Game, Piece, Tile};
+use crate::util::window::*;
+use super::{draw_board, draw_board_grid};
+
+// What left clicking on the board paints with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Brush {
+    Piece(Piece),
+    Garbage,
+}
+
+impl Brush {
+    pub fn label(&self) -> String {
+        match self {
+            Brush::Piece(piece) => format!("{:?}", piece),
+            Brush::Garbage => String::from("Garbage"),
+        }
+    }
+
+    fn tile(&self) -> Tile {
+        match self {
+            Brush::Piece(piece) => Tile::from(*piece),
+            Brush::Garbage => Tile::garbage(),
+        }
+    }
+}
+
+// A position being drawn by hand, which the game can then be started from
+pub struct Editor {
+    pub board: Board,
+    pub piece: Option<Piece>,
+    pub hold: Option<Piece>,
+    pub queue: String, // Typed as piece letters, e.g. "TIJLOSZ"
+    pub pc: PcNumber, // Which PC of the loop the position is part of
+    pub brush: Brush,
+    pub error: Option<&'static str>, // Why the position couldn't be played from
+}
+
+impl Editor {
+    // Starts editing from wherever the game currently is
+    pub fn new(game: &Game) -> Self {
+        Self {
+            board: game.board,
+            piece: game.piece,
+            hold: game.hold,
+            queue: game.queue.iter().map(|piece| format!("{:?}", piece)).collect(),
+            pc: PcNumber::from_start_piece(game.pc_piece_num),
+            brush: Brush::Garbage,
+            error: None,
+        }
+    }
+
+    pub fn clear_board(&mut self) {
+        self.board = Board::new();
+    }
+
+    pub fn parse_queue(&self) -> Result<VecDeque<Piece>, &'static str> {
+        self.queue.chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| Piece::from_char(c).ok_or("The queue can only contain the letters IJLOSTZ"))
+            .collect()
+    }
+
+    // Left click paints with the brush and right click erases, dragging included
+    pub fn handle_mouse(&mut self, x: f32, y: f32) {
+        let tile = if is_mouse_button_down(MouseButton::Left) {
+            self.brush.tile()
+        } else if is_mouse_button_down(MouseButton::Right) {
+            Tile::new()
+        } else {
+            return;
+        };
+        let (mouse_x, mouse_y) = mouse_position();
+        let col = ((mouse_x - x) / tile_size()).floor();
+        let row = ((mouse_y - y) / tile_size()).floor();
+        // Only the visible part of the board can be drawn on
+        if (0.0..10.0).contains(&col) && (3.0..23.0).contains(&row) {
+            self.board.tiles[row as usize][col as usize] = tile;
+        }
+    }
+
+    pub fn draw(&self, x: f32, y: f32) {
+        draw_board(&self.board, x, y);
+        draw_board_grid(x, y);
+    }
+}
@@ -0,0 +1,764 @@
+use crossbeam_channel::{unbounded, Receiver, Sender, TryRecvError};
+use macroquad::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use crate::fumen::decode;
+use crate::logic::{handle_event, AttackTable, Config, GameEvent, KeyAction, Stats};
+use crate::rng::{random_seed, Randomizer};
+use crate::pattern::QueuePattern;
+use crate::search::{
+    find_pc_chance, find_pc_chances, find_pcs, find_setup_coverage, find_setups, rank_pcs, ChanceQuery, Pc, PcChance,
+    PcChanceUpdate, PcObjective,
+};
+use crate::search::Placement;
+use crate::setups::{GuideStep, PcSetup, SetupGuide, SetupLibrary};
+use crate::state::{Board, Game, Piece, RotationSystem};
+use crate::util::font::*;
+use crate::util::window::*;
+use super::{
+    draw_board, draw_board_grid, draw_pc, draw_pc_sequence, draw_piece_preview, draw_setup, get_keycodes, piece_color,
+    poll_input, Editor, Gamepads,
+};
+
+// Where the PC chance calculation is at
+pub enum ChanceStatus {
+    Idle,
+    Failed(&'static str), // Couldn't start, e.g. the stack doesn't fit the PC height
+    Running(usize, usize), // Continuations checked so far, out of how many
+    Done(PcChance),
+}
+
+// Thin macroquad adapter around the game engine -- feeds it keyboard and controller input, runs the PC solver in
+// the background, and draws everything
+pub struct Frontend {
+    pub game: Game,
+    pub stats: Stats,
+    pub pcs: Vec<Pc>, // Ranked best-first, without duplicates
+    found_pcs: Vec<Pc>, // Everything the solver found
+    cancel_flag: Option<Arc<AtomicBool>>,
+    tx: Sender<Vec<Pc>>,
+    rx: Receiver<Vec<Pc>>,
+    start: Instant, // Start of the game clock
+    rotation_system: RotationSystem, // What the solvers assume, kept in sync with the config
+    all_spins: bool,
+    pc_ranking: (PcObjective, AttackTable), // What the PCs are currently ranked by
+    pub chance: ChanceStatus,
+    chance_cancel_flag: Option<Arc<AtomicBool>>,
+    chance_rx: Option<Receiver<PcChanceUpdate>>,
+    setups: Vec<PcSetup>, // Setups buildable from the current position, as of the last search
+    setup_key: Option<SetupKey>, // Position the setups were last searched from
+    setup_cancel_flag: Option<Arc<AtomicBool>>,
+    setup_rx: Option<Receiver<Vec<PcSetup>>>,
+    setup_chances: HashMap<usize, f32>, // Solve chance percentage of each setup, by its index in setups
+    setup_chance_cancel_flag: Option<Arc<AtomicBool>>,
+    setup_chance_rx: Option<Receiver<(usize, f32)>>,
+    pub fumen_error: Option<&'static str>, // Why the last pasted fumen couldn't be loaded
+    setup_library: Arc<SetupLibrary>,
+    pub guide: Option<SetupGuide>, // Build order help for the setup pinned in the SETUPS panel
+    setup_rects: Vec<(Rect, PcSetup)>, // Where each setup was last drawn, for clicking on them
+    pub editor: Option<Editor>, // The position being drawn while in editor mode, which pauses the game
+    pub coverage: Vec<(String, usize, usize)>, // How many of the coverage pattern's queues build each setup
+    pub coverage_error: Option<&'static str>, // Why the coverage pattern couldn't be read
+    coverage_cancel_flag: Option<Arc<AtomicBool>>,
+    coverage_rx: Option<Receiver<(String, usize, usize)>>,
+    pub gamepads: Gamepads,
+}
+
+// Everything the setup finder looks at, so it only reruns when one of these changes
+type SetupKey = (Board, Option<Piece>, Option<Piece>, VecDeque<Piece>, bool, u32, u32, RotationSystem);
+
+// Pairs each setup with its solve chance, keyed by index since setups from different tables can share
+// a name. Best chance first -- setups that haven't been worked out yet go last.
+fn sort_by_chance(setups: &[PcSetup], chances: &HashMap<usize, f32>) -> Vec<(PcSetup, Option<f32>)> {
+    let mut sorted: Vec<(PcSetup, Option<f32>)> = setups.iter().enumerate()
+        .map(|(i, setup)| (setup.clone(), chances.get(&i).copied()))
+        .collect();
+    sorted.sort_by(|(_, a), (_, b)| b.unwrap_or(-1.0).total_cmp(&a.unwrap_or(-1.0)));
+    sorted
+}
+
+impl Frontend {
+    pub fn new(config: &Config) -> Self {
+        let (tx, rx) = unbounded();
+        Self {
+            game: Game::with_randomizer(random_seed(), config.create_randomizer()),
+            stats: Stats::new(),
+            pcs: Vec::new(),
+            found_pcs: Vec::new(),
+            cancel_flag: None,
+            tx,
+            rx,
+            start: Instant::now(),
+            rotation_system: config.rotation_system,
+            all_spins: config.all_spins,
+            pc_ranking: (config.pc_objective, config.attack_table),
+            chance: ChanceStatus::Idle,
+            chance_cancel_flag: None,
+            chance_rx: None,
+            setups: Vec::new(),
+            setup_key: None,
+            setup_cancel_flag: None,
+            setup_rx: None,
+            setup_chances: HashMap::new(),
+            setup_chance_cancel_flag: None,
+            setup_chance_rx: None,
+            fumen_error: None,
+            setup_library: Arc::new(SetupLibrary::load()),
+            guide: None,
+            setup_rects: Vec::new(),
+            editor: None,
+            coverage: Vec::new(),
+            coverage_error: None,
+            coverage_cancel_flag: None,
+            coverage_rx: None,
+            gamepads: Gamepads::new(),
+        }
+    }
+
+    // Starts over with a fresh game whose queue comes from the given seed and randomizer
+    pub fn restart(&mut self, seed: u64, randomizer: Box<dyn Randomizer>) {
+        self.game = Game::with_randomizer(seed, randomizer);
+        self.stats = Stats::new();
+        self.guide = None;
+        self.cancel_pc_chance();
+        self.clear_pcs();
+    }
+
+    // Loads the board from the first page of a fumen, keeping the current queue. In editor mode
+    // the board goes into the editor instead.
+    pub fn load_fumen(&mut self, fumen: &str) {
+        match decode(fumen) {
+            Ok(pages) => {
+                match &mut self.editor {
+                    Some(editor) => editor.board = pages[0].board,
+                    None => self.game.load_board(pages[0].board, &self.stats, self.now()),
+                }
+                self.fumen_error = None;
+            },
+            Err(reason) => self.fumen_error = Some(reason),
+        }
+    }
+
+    pub fn start_editing(&mut self) {
+        self.editor = Some(Editor::new(&self.game));
+    }
+
+    // Starts playing from the position drawn in the editor, with stats as if the PC loop had
+    // gotten there
+    pub fn play_from_editor(&mut self) {
+        let now = self.now();
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        let queue = match editor.parse_queue() {
+            Ok(queue) => queue,
+            Err(reason) => {
+                editor.error = Some(reason);
+                return;
+            },
+        };
+        let filled = editor.board.tiles.iter().flatten().filter(|tile| tile.is_filled()).count() as u32;
+        let pc_piece_num = editor.pc.start_piece();
+        self.stats = Stats::new();
+        self.stats.pieces = pc_piece_num + filled / 4;
+        self.stats.lines = pc_piece_num * 2 / 5;
+        self.game.load_position(editor.board, editor.piece, editor.hold, queue, pc_piece_num, now);
+        self.editor = None;
+        self.guide = None;
+        self.cancel_pc_chance();
+        self.clear_pcs();
+    }
+
+    pub fn setup_library(&self) -> &SetupLibrary {
+        &self.setup_library
+    }
+
+    // Reads the setup files again, e.g. after editing one
+    pub fn reload_setups(&mut self) {
+        self.setup_library = Arc::new(SetupLibrary::load());
+        self.setup_key = None;
+        self.refresh_setups();
+    }
+
+    // Starts looking for buildable setups in the background, unless nothing they depend on changed
+    pub fn refresh_setups(&mut self) {
+        let game = &self.game;
+        let key = (game.board, game.piece, game.hold, game.queue.clone(), game.held, game.pc_piece_num,
+                self.stats.pieces, self.rotation_system);
+        if self.setup_key.as_ref() == Some(&key) {
+            return;
+        }
+        self.setup_key = Some(key);
+        if let Some(flag) = self.setup_cancel_flag.take() {
+            flag.store(true, Ordering::Relaxed);
+        }
+        if let Some(flag) = self.setup_chance_cancel_flag.take() {
+            flag.store(true, Ordering::Relaxed);
+        }
+        // Old setups are drawn against the old board, so don't keep showing them
+        self.setups = Vec::new();
+        self.setup_chances = HashMap::new();
+        let (tx, rx) = unbounded();
+        self.setup_cancel_flag = Some(find_setups(self.setup_library.clone(), self.game.clone(), self.stats,
+                self.rotation_system, tx));
+        self.setup_rx = Some(rx);
+    }
+
+    // Pins the setup so the board shows how to build it, or unpins it if it was already pinned
+    pub fn toggle_pinned_setup(&mut self, setup: PcSetup) {
+        if self.guide.as_ref().is_some_and(|guide| guide.setup == setup) {
+            self.guide = None;
+        } else {
+            self.guide = Some(SetupGuide::new(setup, &self.game, self.rotation_system));
+        }
+    }
+
+    // Pins whichever setup was clicked on in the SETUPS panel
+    fn handle_setup_clicks(&mut self) {
+        if !is_mouse_button_pressed(MouseButton::Left) {
+            return;
+        }
+        let (mouse_x, mouse_y) = mouse_position();
+        let clicked = self.setup_rects.iter()
+            .find(|(rect, _)| rect.contains(vec2(mouse_x, mouse_y)))
+            .map(|(_, setup)| setup.clone());
+        if let Some(setup) = clicked {
+            self.toggle_pinned_setup(setup);
+        }
+    }
+
+    // Works out the solve chance of every setup that can currently be built in the background
+    fn refresh_setup_chances(&mut self) {
+        if let Some(flag) = self.setup_chance_cancel_flag.take() {
+            flag.store(true, Ordering::Relaxed);
+        }
+        self.setup_chances = HashMap::new();
+        let queries = self.setups.iter()
+            .enumerate()
+            .filter_map(|(i, setup)| setup.chance_query(&self.game).map(|query| (i, query)))
+            .collect();
+        let (tx, rx) = unbounded();
+        self.setup_chance_cancel_flag = Some(find_pc_chances(queries, self.rotation_system, tx));
+        self.setup_chance_rx = Some(rx);
+    }
+
+    // Starts working out the chance of a PC from the current position in the background
+    pub fn calculate_pc_chance(&mut self, config: &Config) {
+        self.cancel_pc_chance();
+        let query = if config.pc_chance_pattern.trim().is_empty() {
+            ChanceQuery::from(&self.game, config.pc_chance_height)
+        } else {
+            QueuePattern::parse(&config.pc_chance_pattern)
+                .and_then(|pattern| ChanceQuery::from_pattern(&self.game, pattern, config.pc_chance_height))
+        };
+        match query {
+            Ok(query) => {
+                let (tx, rx) = unbounded();
+                self.chance_cancel_flag = Some(find_pc_chance(query, config.rotation_system, tx));
+                self.chance_rx = Some(rx);
+                self.chance = ChanceStatus::Running(0, 0);
+            },
+            Err(reason) => self.chance = ChanceStatus::Failed(reason),
+        }
+    }
+
+    pub fn cancel_pc_chance(&mut self) {
+        if let Some(flag) = self.chance_cancel_flag.take() {
+            flag.store(true, Ordering::Relaxed);
+        }
+        self.chance_rx = None;
+        self.chance = ChanceStatus::Idle;
+    }
+
+    // Starts checking how many of the pattern's queues build each setup for the current PC, from the
+    // current board
+    pub fn calculate_setup_coverage(&mut self, config: &Config) {
+        self.cancel_setup_coverage();
+        self.coverage = Vec::new();
+        match QueuePattern::parse(&config.coverage_pattern) {
+            Ok(pattern) => {
+                let (tx, rx) = unbounded();
+                self.coverage_cancel_flag = Some(find_setup_coverage(self.setup_library.clone(), self.game.clone(),
+                        self.stats, pattern, self.rotation_system, tx));
+                self.coverage_rx = Some(rx);
+                self.coverage_error = None;
+            },
+            Err(reason) => self.coverage_error = Some(reason),
+        }
+    }
+
+    pub fn cancel_setup_coverage(&mut self) {
+        if let Some(flag) = self.coverage_cancel_flag.take() {
+            flag.store(true, Ordering::Relaxed);
+        }
+        self.coverage_rx = None;
+    }
+
+    pub fn coverage_running(&self) -> bool {
+        self.coverage_rx.is_some()
+    }
+
+    fn clear_pcs(&mut self) {
+        self.pcs = Vec::new();
+        self.found_pcs = Vec::new();
+    }
+
+    pub fn now(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+
+    pub fn refresh_pcs(&mut self) {
+        // Cancel previous solver if it's still running and run new solver
+        if let Some(flag) = self.cancel_flag.take() {
+            flag.store(true, Ordering::Relaxed);
+        }
+        self.cancel_flag = Some(find_pcs(self.game.clone(), self.rotation_system, self.all_spins, self.tx.clone()));
+    }
+
+    pub fn step(&mut self, config: &Config, waiting: bool) {
+        let now = self.now();
+        let mut inputs = poll_input(config, &mut self.gamepads, now);
+        // If we are waiting for a keybind input, don't move in the game -- releases still go
+        // through so that no key gets stuck down
+        if waiting {
+            inputs.retain(|input| !input.pressed);
+        }
+        // The game is paused while editing, but let go of keys so none stay held afterwards
+        if let Some(editor) = &mut self.editor {
+            for &input in inputs.iter().filter(|input| !input.pressed) {
+                handle_event(config, &mut self.stats, &mut self.game, input);
+            }
+            if !waiting {
+                editor.handle_mouse(board_x(), board_y());
+            }
+            return;
+        }
+
+        let mut refresh = false;
+        // Old solutions might not be reachable with different kicks, or have different spins
+        if config.rotation_system != self.rotation_system || config.all_spins != self.all_spins {
+            self.rotation_system = config.rotation_system;
+            self.all_spins = config.all_spins;
+            self.clear_pcs();
+            refresh = true;
+        }
+        for event in self.game.step(config, &mut self.stats, &inputs, now) {
+            match event {
+                // Refresh PC solutions because these might have made some impossible
+                GameEvent::PieceSpawned(_) | GameEvent::Held => {
+                    self.cancel_pc_chance();
+                    refresh = true;
+                },
+                GameEvent::Reset => {
+                    self.guide = None;
+                    self.cancel_pc_chance();
+                    self.clear_pcs();
+                    refresh = true;
+                },
+                GameEvent::Undone | GameEvent::BoardLoaded => {
+                    self.cancel_pc_chance();
+                    self.clear_pcs();
+                    refresh = true;
+                },
+                // Nothing more can be built on a dead board
+                GameEvent::ToppedOut => {
+                    self.guide = None;
+                    self.cancel_pc_chance();
+                    self.clear_pcs();
+                },
+                GameEvent::PiecePlaced(_) => {
+                    // The setup has done its job once the PC is made
+                    if self.game.board.is_empty() {
+                        self.guide = None;
+                    }
+                },
+            }
+        }
+        if refresh {
+            self.refresh_pcs();
+            self.refresh_setups();
+            if let Some(guide) = &mut self.guide {
+                guide.update(&self.game, self.rotation_system);
+            }
+        }
+        if !waiting {
+            self.handle_setup_clicks();
+        }
+        // Check if setups or PC solutions have come in from the other threads
+        if let Some(setups) = self.setup_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.setups = setups;
+            self.setup_rx = None;
+            self.refresh_setup_chances();
+        }
+        if let Some(rx) = &self.setup_chance_rx {
+            while let Ok((i, percent)) = rx.try_recv() {
+                self.setup_chances.insert(i, percent);
+            }
+        }
+        if let Some(rx) = &self.coverage_rx {
+            loop {
+                match rx.try_recv() {
+                    Ok(result) => self.coverage.push(result),
+                    Err(TryRecvError::Empty) => break,
+                    // Every setup has been checked
+                    Err(TryRecvError::Disconnected) => {
+                        self.coverage_rx = None;
+                        self.coverage_cancel_flag = None;
+                        break;
+                    },
+                }
+            }
+        }
+        if let Some(rx) = &self.chance_rx {
+            while let Ok(update) = rx.try_recv() {
+                self.chance = match update {
+                    PcChanceUpdate::Progress(done, total) => ChanceStatus::Running(done, total),
+                    PcChanceUpdate::Done(chance) => ChanceStatus::Done(chance),
+                };
+            }
+        }
+        let mut rerank = false;
+        if let Ok(result) = self.rx.try_recv() {
+            self.found_pcs = result;
+            rerank = true;
+        }
+        if (config.pc_objective, config.attack_table) != self.pc_ranking {
+            self.pc_ranking = (config.pc_objective, config.attack_table);
+            rerank = true;
+        }
+        if rerank {
+            let (objective, attack_table) = self.pc_ranking;
+            self.pcs = rank_pcs(&self.found_pcs, objective, &self.stats, attack_table);
+        }
+    }
+
+    pub fn draw(&mut self, config: &Config, font: Font) {
+        if let Some(editor) = &self.editor {
+            editor.draw(board_x(), board_y());
+            // Current piece goes at the front of the queue so it can be seen too
+            let queue = editor.piece.into_iter().chain(editor.parse_queue().unwrap_or_default()).collect();
+            Frontend::draw_queue_pieces(&queue, queue_x(), queue_y(), 0.75, font);
+            draw_text_ex("HOLD", hold_x() + margin(), hold_y() + tile_size(), text_large(font, WHITE));
+            if let Some(hold) = editor.hold {
+                draw_piece_preview(hold, hold_x() + margin(), hold_y() + text_size_large() + 2.0 * margin(), 0.75);
+            }
+            draw_text_ex("EDITING", piece_num_x() + margin(), piece_num_y() + tile_size(), text_normal(font, WHITE));
+            Frontend::draw_borders();
+            return;
+        }
+        draw_board(&self.game.board, board_x(), board_y());
+        self.draw_piece(board_x(), board_y());
+        self.draw_shadow(board_x(), board_y());
+        self.draw_guide_ghost(board_x(), board_y());
+        self.draw_queue(queue_x(), queue_y(), 0.75, font);
+        self.draw_hold(hold_x(), hold_y(), 0.75, font);
+        self.draw_stats(stats_x(), stats_y(), font);
+        self.draw_piece_num(piece_num_x(), piece_num_y(), font);
+        draw_board_grid(board_x(), board_y());
+        self.draw_finesse_path(finesse_x(), finesse_y(), font);
+        self.draw_pcs(pc_x(), pc_y(), 0.5, font);
+        self.draw_strategy(strategy_x(), strategy_y(), font);
+        self.draw_setups(setup_x(), setup_y(), 0.5, font);
+        if self.game.game_over {
+            Frontend::draw_game_over(config, board_x(), board_y(), font);
+        }
+        Frontend::draw_borders();
+    }
+
+    // Darkens the board and says how to carry on
+    fn draw_game_over(config: &Config, x: f32, y: f32, font: Font) {
+        let top = y + 3.0 * tile_size();
+        draw_rectangle(x, top, board_width(), 20.0 * tile_size(), Color::new(0.0, 0.0, 0.0, 0.7));
+        let key = |action: KeyAction| {
+            get_keycodes(config, action).first().map(|key| format!("{:?}", key)).unwrap_or(format!("{:?}", action))
+        };
+        let lines = [
+            (String::from("GAME OVER"), text_large(font, WHITE)),
+            (format!("Press {} to restart", key(KeyAction::Reset)), text_normal(font, WHITE)),
+            (format!("or {} to undo", key(KeyAction::Undo)), text_normal(font, WHITE)),
+        ];
+        let mut line_y = top + 9.0 * tile_size();
+        for (text, params) in lines {
+            let width = measure_text(&text, Some(params.font), params.font_size, 1.0).width;
+            draw_text_ex(&text, x + (board_width() - width) / 2.0, line_y, params);
+            line_y += params.font_size as f32 + margin();
+        }
+    }
+
+    fn draw_borders() {
+        fn draw_outline(x: f32, y: f32, w: f32, h: f32, thickness: f32, color: Color) {
+            let x1 = x - thickness / 2.0;
+            let x2 = x1 + w;
+            let y1 = y - thickness / 2.0;
+            let y2 = y1 + h;
+            draw_rectangle(x1, y1, w + thickness, thickness, color);
+            draw_rectangle(x1, y1, thickness, h + thickness, color);
+            draw_rectangle(x1, y2, w + thickness, thickness, color);
+            draw_rectangle(x2, y1, thickness, h + thickness, color);
+        }
+        // Board outline
+        draw_outline(board_x(), board_y(), board_width(), board_height(), grid_thickness(), WHITE);
+        // Hold outline
+        draw_outline(hold_x(), hold_y(), hold_width(), hold_height(), grid_thickness(), WHITE);
+        // Finesse outline
+        draw_outline(stats_x(), stats_y(), stats_width(), stats_height(), grid_thickness(), WHITE);
+        // Queue outline
+        draw_outline(queue_x(), queue_y(), queue_width(), queue_height(), grid_thickness(), WHITE);
+        // Piece num outline
+        draw_outline(piece_num_x(), piece_num_y(), piece_num_width(), piece_num_height(), grid_thickness(), WHITE);
+        // Finesse error text outline
+        draw_outline(finesse_x(), finesse_y(), finesse_width(), finesse_height(), grid_thickness(), WHITE);
+        // PC solver outline
+        draw_outline(pc_x(), pc_y(), pc_width(), pc_height(), grid_thickness(), WHITE);
+        // PC strategy outline
+        draw_outline(strategy_x(), strategy_y(), strategy_width(), strategy_height(), grid_thickness(), WHITE);
+        // Setups outline
+        draw_outline(setup_x(), setup_y(), setup_width(), setup_height(), grid_thickness(), WHITE);
+    }
+
+    fn draw_queue(&self, x: f32, y: f32, scale: f32, font: Font) {
+        Frontend::draw_queue_pieces(&self.game.queue, x, y, scale, font);
+    }
+
+    fn draw_queue_pieces(queue: &VecDeque<Piece>, x: f32, y: f32, scale: f32, font: Font) {
+        draw_text_ex("QUEUE", x + margin(), y + tile_size(), text_large(font, WHITE));
+        let mut height: f32 = text_size_large() + 2.0 * margin();
+        // Draw only the first 5 pieces in queue in case we undid moves
+        for &piece in queue.iter().take(5) {
+            let (_, h) = draw_piece_preview(piece, x + margin(), y + height, scale);
+            height += h + queue_gap();
+        }
+    }
+
+    fn draw_piece_num(&self, x: f32, y: f32, font: Font) {
+        let bag = self.stats.pieces / 7 + 1;
+        let piece = self.stats.pieces % 7 + 1;
+        draw_text_ex(&format!("Bag: {}", bag), x + margin(),
+                y + tile_size(), text_normal(font, WHITE));
+        draw_text_ex(&format!("Piece: {}/7", piece), x + margin(),
+                y + tile_size() + text_size_normal(), text_normal(font, WHITE));
+    }
+
+    fn draw_hold(&self, x: f32, y: f32, scale: f32, font: Font) {
+        draw_text_ex("HOLD", x + margin(), y + tile_size(), text_large(font, WHITE));
+        if let Some(hold) = self.game.hold {
+            draw_piece_preview(hold, x + margin(), y + text_size_large() + 2.0 * margin(), scale);
+        }
+        // Hint from the pinned setup
+        if let Some(GuideStep::Place { hold_first: true, .. }) = self.guide.as_ref().map(|guide| &guide.step) {
+            draw_text_ex("Hold next", x + margin(), y + hold_height() - margin(),
+                    text_normal(font, Color::new(1.0, 1.0, 0.5, 1.0)));
+        }
+    }
+
+    fn draw_piece(&self, x: f32, y: f32) {
+        if let Some(piece) = self.game.piece {
+            for &(offset_row, offset_col) in piece.offset_map(self.game.rotation).iter() {
+                draw_rectangle(
+                    x + (self.game.piece_col + offset_col) as f32 * tile_size() + grid_thickness() / 2.0,
+                    y + (self.game.piece_row + offset_row) as f32 * tile_size() + grid_thickness() / 2.0,
+                    tile_size() - grid_thickness(),
+                    tile_size() - grid_thickness(),
+                    piece_color(piece),
+                );
+            }
+        }
+    }
+
+    fn draw_shadow(&self, x: f32, y: f32) {
+        if let Some(piece) = self.game.piece {
+            let shadow_row = self.game.shadow_row();
+            for &(offset_row, offset_col) in piece.offset_map(self.game.rotation).iter() {
+                let mut color = piece_color(piece);
+                color.a = 0.5;
+                draw_rectangle(
+                    x + (self.game.piece_col + offset_col) as f32 * tile_size() + grid_thickness() / 2.0,
+                    y + (shadow_row + offset_row) as f32 * tile_size() + grid_thickness() / 2.0,
+                    tile_size() - grid_thickness(),
+                    tile_size() - grid_thickness(),
+                    color,
+                );
+            }
+        }
+    }
+
+    // Outlines where the next piece of the pinned setup goes
+    fn draw_guide_ghost(&self, x: f32, y: f32) {
+        let Some(GuideStep::Place { placement: Placement::Place { piece, row, col, rotation }, .. })
+                = self.guide.as_ref().map(|guide| &guide.step) else {
+            return;
+        };
+        let mut color = piece_color(*piece);
+        color.a = 0.25;
+        for &(offset_row, offset_col) in piece.offset_map(*rotation).iter() {
+            let cell_x = x + (*col as i8 + offset_col) as f32 * tile_size() + grid_thickness() / 2.0;
+            let cell_y = y + (*row as i8 + offset_row) as f32 * tile_size() + grid_thickness() / 2.0;
+            let size = tile_size() - grid_thickness();
+            draw_rectangle(cell_x, cell_y, size, size, color);
+            draw_rectangle_lines(cell_x, cell_y, size, size, grid_thickness() * 2.0, piece_color(*piece));
+        }
+    }
+
+    fn draw_stats(&self, x: f32, y: f32, font: Font) {
+        draw_text_ex("STATS", x + margin(), y + tile_size(), text_large(font, WHITE));
+        let last_clear = self.stats.last_clear.and_then(|clear| clear.label()).unwrap_or_default();
+        let lines = [
+            format!("Pieces: {}", self.stats.pieces),
+            format!("Lines: {}", self.stats.lines),
+            format!("Inputs: {}", self.stats.inputs),
+            format!("Faults: {}", self.stats.faults),
+            format!("Score: {}", self.stats.score),
+            format!("Attack: {}", self.stats.attack),
+            format!("Combo: {}", self.stats.combo.saturating_sub(1)),
+            format!("B2B: {}", self.stats.b2b.saturating_sub(1)),
+            format!("PCs: {}", self.stats.clear_counts.perfect_clears),
+            format!("Last: {}", last_clear),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            draw_text_ex(line, x + margin(), y + text_size_large() + 2.0 * margin() + i as f32 * text_size_normal(),
+                    text_normal(font, WHITE));
+        }
+    }
+
+    fn draw_finesse_path(&self, x: f32, y: f32, font: Font) {
+        if let Some(path) = &self.game.finesse_path {
+            draw_text_ex(&format!("{:?}", self.game.prev_path), x + margin(),
+                    y + text_size_normal() + margin(), text_normal(font, Color::new(1.0, 0.5, 0.5, 1.0)));
+            draw_text_ex(&format!("{:?}", path), x + margin(),
+                    y + 2.0 * text_size_normal() + margin(), text_normal(font, Color::new(0.5, 1.0, 0.5, 1.0)));
+        }
+    }
+
+    fn draw_pcs(&self, x: f32, y: f32, scale: f32, font: Font) {
+        draw_text_ex("PCs", x + margin(), y + tile_size(), text_large(font, WHITE));
+        let mut height = text_size_large() + 2.0 * margin();
+
+        let (_, attack_table) = self.pc_ranking;
+        for pc in self.pcs.iter() {
+            // Draw the PC strategy this will lead into afterwards
+            let next_pc_piece_num = ((self.stats.lines + pc.height() as u32) * 5 / 2) % 7 + 1;
+            height += text_size_small();
+            draw_text_ex(match next_pc_piece_num {
+                1 => "=> 1st PC",
+                2 => "=> 6th PC",
+                3 => "=> 4th PC",
+                4 => "=> 2nd PC",
+                5 => "=> 7th PC",
+                6 => "=> 5th PC",
+                7 => "=> 3rd PC / DPC",
+                _ => "",
+            }, x + margin(), y + height, text_small(font, WHITE));
+            // Draw what the PC is worth
+            height += text_size_small();
+            draw_text_ex(&format!("{} atk: {}", pc.attack(&self.stats, attack_table), pc.summary()),
+                    x + margin(), y + height, text_small(font, WHITE));
+            if pc.breaks_loop() {
+                height += text_size_small();
+                draw_text_ex("Breaks bag loop", x + margin(), y + height,
+                        text_small(font, Color::new(1.0, 0.5, 0.5, 1.0)));
+            }
+            height += margin();
+            // Draw the solution picture
+            let pc_height = draw_pc(pc, x, y + height, scale);
+            height += pc_height + margin();
+            // Draw the order of piece placements
+            let sequence_height = draw_pc_sequence(pc, x, y + height, scale * 0.25);
+            height += sequence_height + margin();
+        }
+    }
+
+    // Setups that can be built right now and their solve chance if it's known yet, in the order the
+    // SETUPS panel shows them
+    pub fn setups(&self) -> Vec<(PcSetup, Option<f32>)> {
+        sort_by_chance(&self.setups, &self.setup_chances)
+    }
+
+    fn draw_setups(&mut self, x: f32, y: f32, scale: f32, font: Font) {
+        draw_text_ex("SETUPS", x + margin(), y + tile_size(), text_large(font, WHITE));
+        let mut height = text_size_large() + 2.0 * margin();
+        height += self.draw_guide(x, y + height, font);
+
+        self.setup_rects = Vec::new();
+        for (setup, chance) in self.setups() {
+            // Draw the setup build picture
+            let setup_height = draw_setup(&setup, &self.game.board, chance, x, y + height, scale, font);
+            let rect = Rect::new(x, y + height, setup_width(), setup_height);
+            if self.guide.as_ref().is_some_and(|guide| guide.setup == setup) {
+                draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, grid_thickness(), YELLOW);
+            }
+            self.setup_rects.push((rect, setup));
+            height += setup_height + margin();
+        }
+    }
+
+    // Returns the height of the text drawn for the pinned setup
+    fn draw_guide(&self, x: f32, y: f32, font: Font) -> f32 {
+        let Some(guide) = &self.guide else {
+            draw_text_ex("Click a setup to pin it", x + margin(), y + text_size_small(), text_small(font, GRAY));
+            return text_size_small() + margin();
+        };
+        let mut lines = vec![(format!("Pinned: {}", guide.setup.name), WHITE)];
+        match &guide.step {
+            GuideStep::Place { placement, hold_first, path } => {
+                if let Placement::Place { piece, .. } = placement {
+                    let hold = if *hold_first { "Hold, then place " } else { "Place " };
+                    lines.push((format!("{}{:?}", hold, piece), Color::new(1.0, 1.0, 0.5, 1.0)));
+                }
+                lines.push((format!("{:?}", path), Color::new(0.5, 1.0, 0.5, 1.0)));
+            },
+            GuideStep::Built => lines.push((String::from("Built!"), Color::new(0.5, 1.0, 0.5, 1.0))),
+            GuideStep::Deviated => {
+                lines.push((String::from("Off track -- undo to get back"),
+                        Color::new(1.0, 0.5, 0.5, 1.0)));
+            },
+        }
+        let mut height = 0.0;
+        for (line, color) in lines.iter() {
+            height += text_size_small();
+            draw_text_ex(line, x + margin(), y + height, text_small(font, *color));
+        }
+        height + margin()
+    }
+
+    fn draw_strategy(&self, x: f32, y: f32, font: Font) {
+        // If no PC is available, don't draw strategy text
+        if !self.game.board.is_empty() && self.pcs.is_empty() {
+            return;
+        }
+        let piece_num = self.game.pc_piece_num % 7 + 1;
+        draw_text_ex(match piece_num {
+            1 => "1st PC",
+            2 => "6th PC",
+            3 => "4th PC",
+            4 => "2nd PC",
+            5 => "7th PC",
+            6 => "5th PC",
+            7 => "3rd PC / DPC",
+            _ => "",
+        }, x + margin(), y + tile_size(), text_normal(font, WHITE));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Rotation;
+
+    #[test]
+    fn same_named_setups_keep_their_own_chances() {
+        let placement = Placement::place(Piece::O, 21, 0, Rotation::Normal);
+        let setups = [
+            PcSetup::new("PCO", Vec::new()),
+            PcSetup::new("Grace System", Vec::new()),
+            PcSetup::new("PCO", vec![placement]),
+        ];
+        let chances = HashMap::from([(0, 40.0), (2, 90.0)]);
+        let sorted = sort_by_chance(&setups, &chances);
+        let summary: Vec<(&str, usize, Option<f32>)> = sorted.iter()
+            .map(|(setup, chance)| (setup.name.as_str(), setup.placements.len(), *chance))
+            .collect();
+        assert_eq!(summary, [("PCO", 1, Some(90.0)), ("PCO", 0, Some(40.0)), ("Grace System", 0, None)]);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::logic::{Config, InputEvent, KeyAction};
+#[cfg(feature = "gamepad")]
+use crate::logic::PadButton;
+#[cfg(feature = "gamepad")]
+use gilrs::{Button, EventType, Gilrs};
+#[cfg(feature = "gamepad")]
+use strum::IntoEnumIterator;
+
+#[cfg(feature = "gamepad")]
+fn to_gilrs(button: PadButton) -> Button {
+    match button {
+        PadButton::DPadLeft => Button::DPadLeft,
+        PadButton::DPadRight => Button::DPadRight,
+        PadButton::DPadUp => Button::DPadUp,
+        PadButton::DPadDown => Button::DPadDown,
+        PadButton::South => Button::South,
+        PadButton::East => Button::East,
+        PadButton::West => Button::West,
+        PadButton::North => Button::North,
+        PadButton::LeftBumper => Button::LeftTrigger,
+        PadButton::RightBumper => Button::RightTrigger,
+        PadButton::LeftTrigger => Button::LeftTrigger2,
+        PadButton::RightTrigger => Button::RightTrigger2,
+        PadButton::Select => Button::Select,
+        PadButton::Start => Button::Start,
+        PadButton::Guide => Button::Mode,
+        PadButton::LeftStick => Button::LeftThumb,
+        PadButton::RightStick => Button::RightThumb,
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn from_gilrs(button: Button) -> Option<PadButton> {
+    PadButton::iter().find(|&pad_button| to_gilrs(pad_button) == button)
+}
+
+// Every connected controller, read through gilrs. Without the gamepad feature (or if gilrs can't
+// start) there are never any.
+pub struct Gamepads {
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<Gilrs>,
+}
+
+impl Gamepads {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "gamepad")]
+            gilrs: Gilrs::new().ok(),
+        }
+    }
+
+    pub fn supported() -> bool {
+        cfg!(feature = "gamepad")
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        #[cfg(feature = "gamepad")]
+        if let Some(gilrs) = &self.gilrs {
+            return gilrs.gamepads().map(|(_, gamepad)| gamepad.name().to_string()).collect();
+        }
+        Vec::new()
+    }
+
+    // Whether any button bound to the action is held on any controller
+    #[cfg_attr(not(feature = "gamepad"), allow(unused_variables))]
+    pub fn is_down(&self, config: &Config, action: KeyAction) -> bool {
+        #[cfg(feature = "gamepad")]
+        if let Some(gilrs) = &self.gilrs {
+            return gilrs.gamepads().any(|(_, gamepad)| {
+                config.pad_bindings.iter()
+                    .any(|(&button, &bound)| bound == action && gamepad.is_pressed(to_gilrs(button)))
+            });
+        }
+        false
+    }
+
+    // Turns the button presses and releases since the last poll into input events
+    #[cfg_attr(not(feature = "gamepad"), allow(unused_variables, unused_mut))]
+    pub fn poll(&mut self, config: &Config, time: u64) -> Vec<InputEvent> {
+        let mut events: Vec<InputEvent> = Vec::new();
+        #[cfg(feature = "gamepad")]
+        if let Some(gilrs) = &mut self.gilrs {
+            while let Some(event) = gilrs.next_event() {
+                let (button, pressed) = match event.event {
+                    EventType::ButtonPressed(button, _) => (button, true),
+                    EventType::ButtonReleased(button, _) => (button, false),
+                    _ => continue,
+                };
+                let Some(&action) = from_gilrs(button).and_then(|button| config.pad_bindings.get(&button)) else {
+                    continue;
+                };
+                if pressed {
+                    events.push(InputEvent::press(time, action));
+                } else {
+                    events.push(InputEvent::release(time, action));
+                }
+            }
+        }
+        events
+    }
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,8 +1,14 @@
 use egui_macroquad::egui::{self, Ui};
 use macroquad::prelude::*;
 use strum::IntoEnumIterator;
-use crate::logic::{Config, KeyAction};
-use super::lock_game_x;
+use crate::fumen::{board_to_fumen, fumen_link};
+use crate::logic::{AttackTable, Config, Key, KeyAction, LockDelayMode, PadButton, Settings};
+use crate::rng::{QueueRandomizer, RandomizerKind};
+use crate::search::PcObjective;
+use crate::setups::{PcNumber, SetupLibrary};
+use crate::state::{Piece, RotationSystem};
+use super::{find_key_conflicts, from_keycode, get_keycodes, get_keycodes_mut, to_keycode};
+use super::{lock_game_x, Brush, ChanceStatus, Frontend, Gamepads};
 
 fn get_keybind_label(action: KeyAction) -> String {
     String::from(match action {
@@ -19,89 +25,518 @@ fn get_keybind_label(action: KeyAction) -> String {
     })
 }
 
-fn get_keybind_button_text(action: KeyAction, waiting_for: Option<KeyAction>, config: &Config) -> String {
+fn get_add_button_text(action: KeyAction, waiting_for: Option<KeyAction>) -> String {
     if let Some(wait) = waiting_for {
         if wait == action {
             return String::from("Press any key...");
         }
     }
-    format!("{:?}", match action {
-        KeyAction::Left => config.left,
-        KeyAction::Right => config.right,
-        KeyAction::SoftDrop => config.soft_drop,
-        KeyAction::HardDrop => config.hard_drop,
-        KeyAction::RotateCw => config.rotate_cw,
-        KeyAction::RotateCcw => config.rotate_ccw,
-        KeyAction::Rotate180 => config.rotate_180,
-        KeyAction::Hold => config.hold,
-        KeyAction::Reset => config.reset,
-        KeyAction::Undo => config.undo,
-    })
+    String::from("+")
 }
 
-fn add_button(
+// One button per key bound to the action (clicking removes it), then one to bind another key
+fn add_buttons(
     ui: &mut Ui,
     action: KeyAction,
     waiting_for: &mut Option<KeyAction>,
     waiting: &mut bool,
-    config: &Config,
+    config: &mut Config,
+    conflicts: &[(Key, Vec<KeyAction>)],
 ) {
-    if ui.button(get_keybind_button_text(action, *waiting_for, config)).clicked()
-            && waiting_for.is_none() {
-        *waiting_for = Some(action);
-        *waiting = true;
+    ui.horizontal(|ui| {
+        let mut removed: Option<usize> = None;
+        for (i, &key) in get_keycodes(config, action).iter().enumerate() {
+            let mut text = egui::RichText::new(format!("{:?}", key));
+            let mut hover = String::from("Click to unbind");
+            if let Some((_, actions)) = conflicts.iter().find(|(k, _)| *k == key) {
+                text = text.color(egui::Color32::LIGHT_RED);
+                let others: Vec<String> = actions.iter()
+                    .filter(|&&other| other != action)
+                    .map(|&other| get_keybind_label(other))
+                    .collect();
+                hover = format!("Also bound to {}. {}", others.join(", "), hover);
+            }
+            if ui.button(text).on_hover_text(hover).clicked() {
+                removed = Some(i);
+            }
+        }
+        if let Some(i) = removed {
+            get_keycodes_mut(config, action).remove(i);
+        }
+        if ui.button(get_add_button_text(action, *waiting_for)).clicked()
+                && waiting_for.is_none() {
+            *waiting_for = Some(action);
+            *waiting = true;
+        }
+    });
+}
+
+fn draw_profiles(ui: &mut Ui, config: &mut Config, settings: &mut Settings, frontend: &mut Frontend,
+        profile_input: &mut String) {
+    let mut switched_to: Option<Config> = None;
+    egui::ComboBox::from_label("Profile")
+        .selected_text(settings.active().to_string())
+        .show_ui(ui, |ui| {
+            for name in settings.profile_names() {
+                if ui.selectable_label(name == settings.active(), &name).clicked() {
+                    switched_to = settings.switch_profile(&name);
+                }
+            }
+        });
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(profile_input);
+        if ui.button("New profile").clicked() {
+            match settings.new_profile(profile_input, config) {
+                Ok(()) => profile_input.clear(),
+                Err(reason) => settings.error = Some(reason.to_string()),
+            }
+        }
+    });
+    if ui.button("Delete profile").clicked() {
+        switched_to = settings.delete_profile();
+    }
+    if let Some(error) = &settings.error {
+        ui.colored_label(egui::Color32::LIGHT_RED, error);
+    }
+    if let Some(path) = Settings::path() {
+        ui.label(format!("Saved to {}", path.display()));
+    }
+    if let Some(new_config) = switched_to {
+        // The queue might come from somewhere else now
+        let queue_settings = |config: &Config| {
+            (config.randomizer, config.use_custom_queue, config.custom_queue.clone(), config.loop_custom_queue)
+        };
+        let restart = queue_settings(&new_config) != queue_settings(config);
+        *config = new_config;
+        if restart {
+            frontend.restart(config.next_seed(frontend.game.seed), config.create_randomizer());
+        }
+    }
+}
+
+fn draw_pc_chance(ui: &mut Ui, config: &mut Config, frontend: &mut Frontend) {
+    ui.add(egui::Slider::new(&mut config.pc_chance_height, 1..=4).text("Lines"));
+    ui.horizontal(|ui| {
+        ui.label("Pattern");
+        ui.text_edit_singleline(&mut config.pc_chance_pattern);
+    });
+    ui.label("e.g. T,*p4 -- leave blank to try the 7-bag continuations");
+    ui.horizontal(|ui| {
+        if ui.button("Calculate").clicked() {
+            frontend.calculate_pc_chance(config);
+        }
+        if let ChanceStatus::Running(..) = frontend.chance {
+            if ui.button("Cancel").clicked() {
+                frontend.cancel_pc_chance();
+            }
+        }
+    });
+    match &frontend.chance {
+        ChanceStatus::Idle => {},
+        ChanceStatus::Failed(reason) => {
+            ui.label(*reason);
+        },
+        ChanceStatus::Running(done, total) => {
+            ui.label(format!("Checking queues... {}/{}", done, total));
+        },
+        ChanceStatus::Done(chance) => {
+            ui.label(format!("{:.2}% ({}/{})", chance.percent(), chance.successes, chance.total));
+            egui::CollapsingHeader::new(format!("Failing queues ({})", chance.failing.len()))
+                .show(ui, |ui| {
+                    egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                        for sequence in chance.failing.iter() {
+                            ui.label(sequence.iter().map(|piece| format!("{:?}", piece)).collect::<String>());
+                        }
+                    });
+                });
+        },
+    }
+}
+
+fn draw_seed(ui: &mut Ui, config: &mut Config, frontend: &mut Frontend, seed_input: &mut String) {
+    ui.label(format!("Current seed: {}", frontend.game.seed));
+    ui.checkbox(&mut config.pin_seed, "Pin seed (Reset replays it)");
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(seed_input);
+        // Replays the typed seed, or the current one if nothing is typed
+        if ui.button("Replay").clicked() {
+            let seed = if seed_input.trim().is_empty() {
+                Some(frontend.game.seed)
+            } else {
+                seed_input.trim().parse().ok()
+            };
+            if let Some(seed) = seed {
+                frontend.restart(seed, config.create_randomizer());
+            }
+        }
+    });
+}
+
+fn draw_custom_queue(ui: &mut Ui, config: &mut Config, frontend: &mut Frontend) {
+    ui.horizontal(|ui| {
+        ui.label("Custom queue");
+        ui.text_edit_singleline(&mut config.custom_queue);
+    });
+    let old_looping = config.loop_custom_queue;
+    ui.checkbox(&mut config.loop_custom_queue, "Loop (otherwise 7-bags follow)");
+    let parsed = QueueRandomizer::new(&config.custom_queue, config.loop_custom_queue);
+    if let Err(reason) = &parsed {
+        if !config.custom_queue.trim().is_empty() {
+            ui.label(*reason);
+        }
+    }
+    ui.horizontal(|ui| {
+        if ui.add_enabled(parsed.is_ok(), egui::Button::new("Play queue")).clicked()
+                || (config.use_custom_queue && config.loop_custom_queue != old_looping) {
+            config.use_custom_queue = true;
+            frontend.restart(config.next_seed(frontend.game.seed), config.create_randomizer());
+        }
+        if config.use_custom_queue && ui.button("Back to randomizer").clicked() {
+            config.use_custom_queue = false;
+            frontend.restart(config.next_seed(frontend.game.seed), config.create_randomizer());
+        }
+    });
+}
+
+fn copy_fumen(ui: &Ui, fumen: &str) {
+    let link = fumen_link(fumen);
+    ui.output_mut(|output| output.copied_text = link);
+}
+
+fn draw_fumen(ui: &mut Ui, frontend: &mut Frontend, fumen_input: &mut String) {
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(fumen_input);
+        if ui.button("Load").clicked() {
+            frontend.load_fumen(fumen_input);
+        }
+    });
+    if let Some(reason) = frontend.fumen_error {
+        ui.label(reason);
+    }
+    if ui.button("Copy board").clicked() {
+        copy_fumen(ui, &board_to_fumen(&frontend.game.board));
+    }
+    egui::CollapsingHeader::new(format!("Copy PC solution ({})", frontend.pcs.len()))
+        .show(ui, |ui| {
+            for (i, pc) in frontend.pcs.iter().enumerate() {
+                if ui.button(format!("{}. {}", i + 1, pc.summary())).clicked() {
+                    copy_fumen(ui, &pc.to_fumen());
+                }
+            }
+        });
+    let setups = frontend.setups();
+    egui::CollapsingHeader::new(format!("Copy setup ({})", setups.len()))
+        .show(ui, |ui| {
+            for (setup, _) in setups.iter() {
+                if ui.button(&setup.name).clicked() {
+                    copy_fumen(ui, &setup.to_fumen(&frontend.game.board));
+                }
+            }
+        });
+}
+
+fn piece_combo_box(ui: &mut Ui, label: &str, piece: &mut Option<Piece>) {
+    let text = |piece: Option<Piece>| piece.map(|piece| format!("{:?}", piece)).unwrap_or(String::from("None"));
+    egui::ComboBox::from_label(label)
+        .selected_text(text(*piece))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(piece, None, text(None));
+            for option in Piece::iter() {
+                ui.selectable_value(piece, Some(option), text(Some(option)));
+            }
+        });
+}
+
+fn draw_gamepad(ui: &mut Ui, config: &mut Config, frontend: &Frontend) {
+    if !Gamepads::supported() {
+        ui.label("Built without controller support (build with --features gamepad)");
+        return;
+    }
+    let names = frontend.gamepads.names();
+    if names.is_empty() {
+        ui.label("No controllers connected");
+    }
+    for name in names {
+        ui.label(format!("Connected: {}", name));
+    }
+    let text = |action: Option<KeyAction>| action.map(get_keybind_label).unwrap_or(String::from("None"));
+    for button in PadButton::iter() {
+        let mut action = config.pad_bindings.get(&button).copied();
+        egui::ComboBox::from_label(button.label())
+            .selected_text(text(action))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut action, None, text(None));
+                for option in KeyAction::iter() {
+                    ui.selectable_value(&mut action, Some(option), text(Some(option)));
+                }
+            });
+        match action {
+            Some(action) => config.pad_bindings.insert(button, action),
+            None => config.pad_bindings.remove(&button),
+        };
+    }
+}
+
+fn draw_editor(ui: &mut Ui, frontend: &mut Frontend) {
+    let Some(editor) = &mut frontend.editor else {
+        if ui.button("Edit position").clicked() {
+            frontend.start_editing();
+        }
+        return;
+    };
+    ui.label("Left click paints, right click erases");
+    ui.horizontal_wrapped(|ui| {
+        for brush in Piece::iter().map(Brush::Piece).chain([Brush::Garbage]) {
+            ui.selectable_value(&mut editor.brush, brush, brush.label());
+        }
+    });
+    if ui.button("Clear board").clicked() {
+        editor.clear_board();
+    }
+    piece_combo_box(ui, "Current piece", &mut editor.piece);
+    piece_combo_box(ui, "Hold", &mut editor.hold);
+    ui.horizontal(|ui| {
+        ui.label("Queue");
+        ui.text_edit_singleline(&mut editor.queue);
+    });
+    egui::ComboBox::from_label("PC")
+        .selected_text(editor.pc.label())
+        .show_ui(ui, |ui| {
+            for pc in PcNumber::iter() {
+                ui.selectable_value(&mut editor.pc, pc, pc.label());
+            }
+        });
+    if let Some(reason) = editor.error {
+        ui.label(reason);
+    }
+    ui.horizontal(|ui| {
+        if ui.button("Play").clicked() {
+            frontend.play_from_editor();
+        }
+        if ui.button("Cancel").clicked() {
+            frontend.editor = None;
+        }
+    });
+}
+
+fn draw_setup_coverage(ui: &mut Ui, config: &mut Config, frontend: &mut Frontend) {
+    ui.horizontal(|ui| {
+        ui.label("Pattern");
+        ui.text_edit_singleline(&mut config.coverage_pattern);
+    });
+    ui.horizontal(|ui| {
+        if ui.button("Check coverage").clicked() {
+            frontend.calculate_setup_coverage(config);
+        }
+        if frontend.coverage_running() && ui.button("Cancel").clicked() {
+            frontend.cancel_setup_coverage();
+        }
+    });
+    if let Some(reason) = frontend.coverage_error {
+        ui.label(reason);
+    }
+    if frontend.coverage.is_empty() {
+        return;
+    }
+    // Best coverage first
+    let mut coverage = frontend.coverage.clone();
+    coverage.sort_by(|(_, built_a, total_a), (_, built_b, total_b)| {
+        (built_b * total_a).cmp(&(built_a * total_b))
+    });
+    egui::CollapsingHeader::new(format!("Coverage ({})", coverage.len()))
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                for (name, built, total) in coverage.iter() {
+                    let percent = if *total == 0 { 0.0 } else { 100.0 * *built as f32 / *total as f32 };
+                    ui.label(format!("{}: {:.1}% ({}/{})", name, percent, built, total));
+                }
+            });
+        });
+}
+
+fn draw_setup_library(ui: &mut Ui, frontend: &mut Frontend) {
+    ui.label(format!("{} setups loaded", frontend.setup_library().len()));
+    if let Some(dir) = SetupLibrary::user_dir() {
+        ui.label(format!("Custom setups: {}", dir.display()));
+    }
+    if ui.button("Reload").clicked() {
+        frontend.reload_setups();
+    }
+    let errors = &frontend.setup_library().errors;
+    if !errors.is_empty() {
+        egui::CollapsingHeader::new(format!("Errors ({})", errors.len()))
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                    for error in errors.iter() {
+                        ui.colored_label(egui::Color32::LIGHT_RED, error.to_string());
+                    }
+                });
+            });
+    }
+}
+
+// Returns true when a text field has keyboard focus, so the game shouldn't take the input
+// What has been typed into the settings panel's text fields
+#[derive(Default)]
+pub struct TextInputs {
+    pub seed: String,
+    pub fumen: String,
+    pub profile: String,
+}
+
+impl TextInputs {
+    pub fn new() -> Self {
+        Self {
+            seed: String::new(),
+            fumen: String::new(),
+            profile: String::new(),
+        }
     }
 }
 
-pub fn draw_ui(config: &mut Config, waiting_for: &mut Option<KeyAction>, waiting: &mut bool) {
+pub fn draw_ui(
+    config: &mut Config,
+    settings: &mut Settings,
+    frontend: &mut Frontend,
+    inputs: &mut TextInputs,
+    waiting_for: &mut Option<KeyAction>,
+    waiting: &mut bool,
+) -> bool {
+    let mut typing = false;
     egui_macroquad::ui(|egui_ctx| {
         egui_ctx.set_pixels_per_point((screen_width() / 1500.0).max(1.0));
 
         egui::SidePanel::left("game_settings")
             .resizable(false)
             .show(egui_ctx, |ui| {
+                ui.heading("Profiles");
+                draw_profiles(ui, config, settings, frontend, &mut inputs.profile);
+
+                ui.separator();
+
                 ui.heading("Game Settings");
                 ui.add(egui::Slider::new(&mut config.gravity, 0.0..=30.0).text("Gravity (units/s)"));
+                ui.add(egui::Slider::new(&mut config.grace_period, 0..=2000).text("Lock delay (ms)"));
+                egui::ComboBox::from_label("Lock delay resets")
+                    .selected_text(config.lock_delay_mode.label())
+                    .show_ui(ui, |ui| {
+                        for mode in LockDelayMode::iter() {
+                            ui.selectable_value(&mut config.lock_delay_mode, mode, mode.label());
+                        }
+                    });
                 ui.add(egui::Slider::new(&mut config.das, 0..=500).text("DAS (ms)"));
                 ui.add(egui::Slider::new(&mut config.arr, 0..=50).text("ARR (ms/unit)"));
-                ui.add(egui::Slider::new(&mut config.sdr, 0..=50).text("SDR (ms/unit)"));
+                ui.add(egui::Slider::new(&mut config.dcd, 0..=100).text("DCD (ms)"));
+                ui.checkbox(&mut config.das_carry, "Keep DAS charged between pieces");
+                ui.add_enabled(!config.infinite_sdf, egui::Slider::new(&mut config.sdf, 1.0..=40.0).text("SDF (x gravity)"));
+                ui.checkbox(&mut config.infinite_sdf, "Infinite SDF");
+                egui::ComboBox::from_label("Rotation system")
+                    .selected_text(config.rotation_system.label())
+                    .show_ui(ui, |ui| {
+                        for system in RotationSystem::iter() {
+                            ui.selectable_value(&mut config.rotation_system, system, system.label());
+                        }
+                    });
+                ui.checkbox(&mut config.all_spins, "All-spins");
+                egui::ComboBox::from_label("Attack table")
+                    .selected_text(config.attack_table.label())
+                    .show_ui(ui, |ui| {
+                        for table in AttackTable::iter() {
+                            ui.selectable_value(&mut config.attack_table, table, table.label());
+                        }
+                    });
+                egui::ComboBox::from_label("PC ranking")
+                    .selected_text(config.pc_objective.label())
+                    .show_ui(ui, |ui| {
+                        for objective in PcObjective::iter() {
+                            ui.selectable_value(&mut config.pc_objective, objective, objective.label());
+                        }
+                    })
+                    .response
+                    .on_hover_text("Attack and T-spins assume each piece that can rotate into its spot does, \
+                        with whichever kick makes the best spin");
+
+                ui.separator();
+
+                ui.heading("Queue");
+                let old_randomizer = config.randomizer;
+                egui::ComboBox::from_label("Randomizer")
+                    .selected_text(config.randomizer.label())
+                    .show_ui(ui, |ui| {
+                        for kind in RandomizerKind::iter() {
+                            ui.selectable_value(&mut config.randomizer, kind, kind.label());
+                        }
+                    });
+                // Switching randomizers only makes sense from a fresh game
+                if config.randomizer != old_randomizer {
+                    config.use_custom_queue = false;
+                    frontend.restart(config.next_seed(frontend.game.seed), config.create_randomizer());
+                }
+                draw_custom_queue(ui, config, frontend);
+                draw_seed(ui, config, frontend, &mut inputs.seed);
+
+                ui.separator();
+
+                ui.heading("Board Editor");
+                draw_editor(ui, frontend);
+
+                ui.separator();
+
+                ui.heading("Fumen");
+                draw_fumen(ui, frontend, &mut inputs.fumen);
+
+                ui.separator();
+
+                ui.heading("Setup Library");
+                draw_setup_library(ui, frontend);
+                ui.label("Setup coverage");
+                draw_setup_coverage(ui, config, frontend);
+
+                ui.separator();
+
+                ui.heading("PC Chance");
+                draw_pc_chance(ui, config, frontend);
 
                 ui.separator();
 
                 ui.heading("Key Bindings");
+                let conflicts = find_key_conflicts(config);
                 egui::Grid::new("keybind_table")
                     .striped(true)
                     .show(ui, |ui| {
                         for action in KeyAction::iter() {
                             ui.label(get_keybind_label(action));
-                            add_button(ui, action, waiting_for, waiting, config);
+                            add_buttons(ui, action, waiting_for, waiting, config, &conflicts);
                             ui.end_row();
                         }
                     });
+                for (key, actions) in &conflicts {
+                    let labels: Vec<String> = actions.iter().map(|&action| get_keybind_label(action)).collect();
+                    ui.colored_label(egui::Color32::LIGHT_RED, format!("{:?} is bound to {}", key, labels.join(" and ")));
+                }
+
+                ui.separator();
+
+                ui.heading("Controller");
+                draw_gamepad(ui, config, frontend);
             });
         *lock_game_x() = egui_ctx.used_rect().right();
+        typing = egui_ctx.wants_keyboard_input();
     });
     egui_macroquad::draw();
+    typing
 }
 
 pub fn wait_for_keybind(
     config: &mut Config,
     waiting_for: &mut Option<KeyAction>,
-    keycode_set: &mut Option<KeyCode>,
+    keycode_set: &mut Option<Key>,
 ) {
     if let Some(action) = waiting_for {
-        if let Some(key) = get_last_key_pressed() {
-            match action {
-                KeyAction::Left => config.left = key,
-                KeyAction::Right => config.right = key,
-                KeyAction::SoftDrop => config.soft_drop = key,
-                KeyAction::HardDrop => config.hard_drop = key,
-                KeyAction::RotateCw => config.rotate_cw = key,
-                KeyAction::RotateCcw => config.rotate_ccw = key,
-                KeyAction::Rotate180 => config.rotate_180 = key,
-                KeyAction::Hold => config.hold = key,
-                KeyAction::Reset => config.reset = key,
-                KeyAction::Undo => config.undo = key,
+        if let Some(key) = get_last_key_pressed().and_then(from_keycode) {
+            let keys = get_keycodes_mut(config, *action);
+            if !keys.contains(&key) {
+                keys.push(key);
             }
             *waiting_for = None;
             *keycode_set = Some(key);
@@ -109,9 +544,9 @@ pub fn wait_for_keybind(
     }
 }
 
-pub fn check_done_waiting(waiting: &mut bool, keycode_set: &mut Option<KeyCode>) {
+pub fn check_done_waiting(waiting: &mut bool, keycode_set: &mut Option<Key>) {
     if let Some(key) = keycode_set {
-        if is_key_released(*key) {
+        if is_key_released(to_keycode(*key)) {
             *waiting = false;
             *keycode_set = None;
         }
@@ -0,0 +1,203 @@
+use macroquad::prelude::*;
+use strum::IntoEnumIterator;
+use crate::logic::{Config, InputEvent, Key, KeyAction};
+use super::Gamepads;
+
+pub fn to_keycode(key: Key) -> KeyCode {
+    match key {
+        Key::Space => KeyCode::Space,
+        Key::Apostrophe => KeyCode::Apostrophe,
+        Key::Comma => KeyCode::Comma,
+        Key::Minus => KeyCode::Minus,
+        Key::Period => KeyCode::Period,
+        Key::Slash => KeyCode::Slash,
+        Key::Key0 => KeyCode::Key0,
+        Key::Key1 => KeyCode::Key1,
+        Key::Key2 => KeyCode::Key2,
+        Key::Key3 => KeyCode::Key3,
+        Key::Key4 => KeyCode::Key4,
+        Key::Key5 => KeyCode::Key5,
+        Key::Key6 => KeyCode::Key6,
+        Key::Key7 => KeyCode::Key7,
+        Key::Key8 => KeyCode::Key8,
+        Key::Key9 => KeyCode::Key9,
+        Key::Semicolon => KeyCode::Semicolon,
+        Key::Equal => KeyCode::Equal,
+        Key::A => KeyCode::A,
+        Key::B => KeyCode::B,
+        Key::C => KeyCode::C,
+        Key::D => KeyCode::D,
+        Key::E => KeyCode::E,
+        Key::F => KeyCode::F,
+        Key::G => KeyCode::G,
+        Key::H => KeyCode::H,
+        Key::I => KeyCode::I,
+        Key::J => KeyCode::J,
+        Key::K => KeyCode::K,
+        Key::L => KeyCode::L,
+        Key::M => KeyCode::M,
+        Key::N => KeyCode::N,
+        Key::O => KeyCode::O,
+        Key::P => KeyCode::P,
+        Key::Q => KeyCode::Q,
+        Key::R => KeyCode::R,
+        Key::S => KeyCode::S,
+        Key::T => KeyCode::T,
+        Key::U => KeyCode::U,
+        Key::V => KeyCode::V,
+        Key::W => KeyCode::W,
+        Key::X => KeyCode::X,
+        Key::Y => KeyCode::Y,
+        Key::Z => KeyCode::Z,
+        Key::LeftBracket => KeyCode::LeftBracket,
+        Key::Backslash => KeyCode::Backslash,
+        Key::RightBracket => KeyCode::RightBracket,
+        Key::GraveAccent => KeyCode::GraveAccent,
+        Key::World1 => KeyCode::World1,
+        Key::World2 => KeyCode::World2,
+        Key::Escape => KeyCode::Escape,
+        Key::Enter => KeyCode::Enter,
+        Key::Tab => KeyCode::Tab,
+        Key::Backspace => KeyCode::Backspace,
+        Key::Insert => KeyCode::Insert,
+        Key::Delete => KeyCode::Delete,
+        Key::Right => KeyCode::Right,
+        Key::Left => KeyCode::Left,
+        Key::Down => KeyCode::Down,
+        Key::Up => KeyCode::Up,
+        Key::PageUp => KeyCode::PageUp,
+        Key::PageDown => KeyCode::PageDown,
+        Key::Home => KeyCode::Home,
+        Key::End => KeyCode::End,
+        Key::CapsLock => KeyCode::CapsLock,
+        Key::ScrollLock => KeyCode::ScrollLock,
+        Key::NumLock => KeyCode::NumLock,
+        Key::PrintScreen => KeyCode::PrintScreen,
+        Key::Pause => KeyCode::Pause,
+        Key::F1 => KeyCode::F1,
+        Key::F2 => KeyCode::F2,
+        Key::F3 => KeyCode::F3,
+        Key::F4 => KeyCode::F4,
+        Key::F5 => KeyCode::F5,
+        Key::F6 => KeyCode::F6,
+        Key::F7 => KeyCode::F7,
+        Key::F8 => KeyCode::F8,
+        Key::F9 => KeyCode::F9,
+        Key::F10 => KeyCode::F10,
+        Key::F11 => KeyCode::F11,
+        Key::F12 => KeyCode::F12,
+        Key::F13 => KeyCode::F13,
+        Key::F14 => KeyCode::F14,
+        Key::F15 => KeyCode::F15,
+        Key::F16 => KeyCode::F16,
+        Key::F17 => KeyCode::F17,
+        Key::F18 => KeyCode::F18,
+        Key::F19 => KeyCode::F19,
+        Key::F20 => KeyCode::F20,
+        Key::F21 => KeyCode::F21,
+        Key::F22 => KeyCode::F22,
+        Key::F23 => KeyCode::F23,
+        Key::F24 => KeyCode::F24,
+        Key::F25 => KeyCode::F25,
+        Key::Kp0 => KeyCode::Kp0,
+        Key::Kp1 => KeyCode::Kp1,
+        Key::Kp2 => KeyCode::Kp2,
+        Key::Kp3 => KeyCode::Kp3,
+        Key::Kp4 => KeyCode::Kp4,
+        Key::Kp5 => KeyCode::Kp5,
+        Key::Kp6 => KeyCode::Kp6,
+        Key::Kp7 => KeyCode::Kp7,
+        Key::Kp8 => KeyCode::Kp8,
+        Key::Kp9 => KeyCode::Kp9,
+        Key::KpDecimal => KeyCode::KpDecimal,
+        Key::KpDivide => KeyCode::KpDivide,
+        Key::KpMultiply => KeyCode::KpMultiply,
+        Key::KpSubtract => KeyCode::KpSubtract,
+        Key::KpAdd => KeyCode::KpAdd,
+        Key::KpEnter => KeyCode::KpEnter,
+        Key::KpEqual => KeyCode::KpEqual,
+        Key::LeftShift => KeyCode::LeftShift,
+        Key::LeftControl => KeyCode::LeftControl,
+        Key::LeftAlt => KeyCode::LeftAlt,
+        Key::LeftSuper => KeyCode::LeftSuper,
+        Key::RightShift => KeyCode::RightShift,
+        Key::RightControl => KeyCode::RightControl,
+        Key::RightAlt => KeyCode::RightAlt,
+        Key::RightSuper => KeyCode::RightSuper,
+        Key::Menu => KeyCode::Menu,
+    }
+}
+
+// The bindable key for a key macroquad reports, if it's one that can be bound
+pub fn from_keycode(code: KeyCode) -> Option<Key> {
+    Key::iter().find(|&key| to_keycode(key) == code)
+}
+
+pub fn get_keycodes(config: &Config, action: KeyAction) -> &[Key] {
+    match action {
+        KeyAction::Left => &config.left,
+        KeyAction::Right => &config.right,
+        KeyAction::SoftDrop => &config.soft_drop,
+        KeyAction::HardDrop => &config.hard_drop,
+        KeyAction::RotateCw => &config.rotate_cw,
+        KeyAction::RotateCcw => &config.rotate_ccw,
+        KeyAction::Rotate180 => &config.rotate_180,
+        KeyAction::Hold => &config.hold,
+        KeyAction::Reset => &config.reset,
+        KeyAction::Undo => &config.undo,
+    }
+}
+
+pub fn get_keycodes_mut(config: &mut Config, action: KeyAction) -> &mut Vec<Key> {
+    match action {
+        KeyAction::Left => &mut config.left,
+        KeyAction::Right => &mut config.right,
+        KeyAction::SoftDrop => &mut config.soft_drop,
+        KeyAction::HardDrop => &mut config.hard_drop,
+        KeyAction::RotateCw => &mut config.rotate_cw,
+        KeyAction::RotateCcw => &mut config.rotate_ccw,
+        KeyAction::Rotate180 => &mut config.rotate_180,
+        KeyAction::Hold => &mut config.hold,
+        KeyAction::Reset => &mut config.reset,
+        KeyAction::Undo => &mut config.undo,
+    }
+}
+
+// Keys bound to more than one action, along with the actions they're bound to
+pub fn find_key_conflicts(config: &Config) -> Vec<(Key, Vec<KeyAction>)> {
+    let mut bound: Vec<(Key, Vec<KeyAction>)> = Vec::new();
+    for action in KeyAction::iter() {
+        for &key in get_keycodes(config, action) {
+            match bound.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, actions)) => actions.push(action),
+                None => bound.push((key, vec![action])),
+            }
+        }
+    }
+    bound.retain(|(_, actions)| actions.len() > 1);
+    bound
+}
+
+// Turns this frame's keyboard and controller presses and releases into input events for the
+// game. Every key or button bound to an action presses it, but it's only released once none of
+// them are held.
+pub fn poll_input(config: &Config, gamepads: &mut Gamepads, time: u64) -> Vec<InputEvent> {
+    let mut events = gamepads.poll(config, time);
+    for action in KeyAction::iter() {
+        let keys = get_keycodes(config, action);
+        for &key in keys {
+            if is_key_pressed(to_keycode(key)) {
+                events.push(InputEvent::press(time, action));
+            }
+        }
+        if keys.iter().any(|&key| is_key_released(to_keycode(key))) {
+            events.push(InputEvent::release(time, action));
+        }
+    }
+    events.retain(|event| {
+        let keys = get_keycodes(config, event.action);
+        let held = keys.iter().any(|&key| is_key_down(to_keycode(key))) || gamepads.is_down(config, event.action);
+        event.pressed || !held
+    });
+    events
+}
@@ -1,9 +1,19 @@
 use std::sync::{Mutex, MutexGuard};
 use lazy_static::lazy_static;
 
+mod draw;
+mod editor;
+mod frontend;
+mod gamepad;
 mod helpers;
+mod input;
 
+pub use draw::*;
+pub use editor::*;
+pub use frontend::*;
+pub use gamepad::*;
 pub use helpers::*;
+pub use input::*;
 
 lazy_static! {
     static ref GAME_X: Mutex<f32> = Mutex::new(0.0);
@@ -115,7 +115,7 @@ pub fn stats_width() -> f32 {
 
 #[inline(always)]
 pub fn stats_height() -> f32 {
-    (board_height() - (hold_y() - board_y())) / 3.0
+    board_height() - (stats_y() - board_y())
 }
 
 #[inline(always)]